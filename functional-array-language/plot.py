@@ -38,7 +38,8 @@ def summaryFromCSV(path):
       'e-nodes':'int',
       'e-classes':'int',
       'total_time':'float',
-      'found':'bool'
+      'found':'bool',
+      'egraph_memory':'int'
     }
     return lastIterationAndTotalFromCSV(path, columns, columns_from_last_iteration, columns_as_total)
   else:
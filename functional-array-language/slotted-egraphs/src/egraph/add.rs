@@ -98,6 +98,9 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         self.semify_app_id(syn)
     }
 
+    /// Looks up the class containing `n`.
+    ///
+    /// If the e-graph is not clean, this might miss e-nodes that are only equal by congruence, see [EGraph::rebuild].
     pub fn lookup(&self, n: &L) -> Option<AppliedId> {
         self.lookup_internal(&self.shape(n))
     }
@@ -149,9 +152,16 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
 
         // we use semantic_add so that the redundancy, symmetry and congruence checks run on it.
         let t = syn_enode_fresh.weak_shape();
+        let was_clean = self.is_clean();
         self.raw_add_to_class(i, t.clone(), i);
-        self.pending.insert(t.0, PendingType::Full);
-        self.rebuild();
+        if was_clean {
+            // Adding to a clean e-graph keeps it clean.
+            self.pending.insert(t.0, PendingType::Full);
+            self.rebuild();
+        } else {
+            // Otherwise, the pending unions are left for the next rebuild.
+            self.handle_pending(t.0, PendingType::Full);
+        }
 
         self.mk_syn_applied_id(i, fresh_to_old)
     }
@@ -0,0 +1,73 @@
+use crate::*;
+
+impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Drops the dead e-classes that are no longer needed, and renumbers the remaining ones densely.
+    ///
+    /// Afterwards, the alive e-classes have the [Id]s `0..n`.
+    /// Dead e-classes are only kept, if they are the syntactic origin of an e-node that is still in the e-graph.
+    /// E-classes removed by [EGraph::prune] are dropped as well.
+    ///
+    /// Returns a map from the old [Id]s to the new ones. [Id]s that are missing from this map were dropped.
+    /// In order to keep a dead [AppliedId], normalize it using [EGraph::find_applied_id] before compacting.
+    /// [Id]s stored within the analysis data are not updated.
+    ///
+    /// This is not available when explanations are enabled, as the proofs would need to be migrated as well.
+    #[cfg(not(feature = "explanations"))]
+    pub fn compact(&mut self) -> HashMap<Id, Id> {
+        self.rebuild();
+
+        let live = self.ids();
+
+        // The syntactic e-nodes of the kept e-classes may refer to dead e-classes, which then need to be kept as well.
+        let mut kept: HashSet<Id> = HashSet::default();
+        let mut todo: Vec<Id> = live.clone();
+        for i in &live {
+            todo.extend(self.classes[i].nodes.values().map(|psn| psn.src_id));
+        }
+        while let Some(i) = todo.pop() {
+            if !kept.insert(i) { continue; }
+            todo.extend(self.classes[&i].syn_enode.ids());
+        }
+
+        let mut dead: Vec<Id> = kept.iter().copied().filter(|x| !self.is_alive(*x)).collect();
+        dead.sort();
+
+        let map: HashMap<Id, Id> = live.iter().chain(&dead)
+                                       .enumerate()
+                                       .map(|(new, old)| (*old, Id(new)))
+                                       .collect();
+        let rename = |x: AppliedId| AppliedId::new(map[&x.id], x.m);
+        let rename_node = |n: &L| n.map_applied_ids(rename);
+
+        let mut unionfind = Vec::with_capacity(map.len());
+        let mut classes = HashMap::default();
+        for old in live.iter().chain(&dead) {
+            let leader = self.unionfind_get(*old);
+            unionfind.push(self.refl_pai(&rename(leader)));
+
+            let mut c = self.classes.remove(old).unwrap();
+            c.nodes = c.nodes.into_iter()
+                             .map(|(sh, psn)| (rename_node(&sh), ProvenSourceNode { src_id: map[&psn.src_id], ..psn }))
+                             .collect();
+            c.usages = c.usages.iter().map(rename_node).collect();
+            c.syn_enode = rename_node(&c.syn_enode);
+            classes.insert(map[old], c);
+        }
+
+        self.hashcons = self.hashcons.iter().map(|(sh, i)| (rename_node(sh), map[i])).collect();
+        self.syn_hashcons = self.syn_hashcons.iter()
+                                .filter(|(_, x)| map.contains_key(&x.id))
+                                .map(|(sh, x)| (rename_node(sh), rename(x.clone())))
+                                .collect();
+        self.facts = std::mem::take(&mut self.facts).into_iter()
+                         .map(|(i, fs)| (map[&i], fs.into_iter().map(|(f, name)| (rename(f), name)).collect()))
+                         .collect();
+        self.classes = classes;
+        *self.unionfind.write().unwrap() = unionfind;
+        self.pruned = HashSet::default();
+
+        if CHECKS { self.check(); }
+
+        map
+    }
+}
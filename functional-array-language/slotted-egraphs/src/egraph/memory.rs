@@ -0,0 +1,94 @@
+use crate::*;
+use std::fmt::{self, Display, Formatter};
+use std::mem::size_of;
+
+/// An estimate of the memory held by an [EGraph], in bytes, split up by component.
+///
+/// Only the sizes of the data structures are estimated, heap memory owned by the analysis data is not included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub unionfind: usize,
+    pub hashcons: usize,
+    pub syn_hashcons: usize,
+
+    /// The e-classes, including their e-nodes and usages, but excluding their groups.
+    pub classes: usize,
+
+    /// The symmetry groups of the e-classes.
+    pub groups: usize,
+
+    /// The e-nodes that are waiting for the next [EGraph::rebuild].
+    pub pending: usize,
+
+    /// The proofs collected for explanations. This is 0, if explanations are disabled.
+    /// Proofs that are shared with a [fork](EGraph::fork) are counted for both e-graphs.
+    pub proof_registry: usize,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.unionfind + self.hashcons + self.syn_hashcons + self.classes + self.groups + self.pending + self.proof_registry
+    }
+}
+
+impl Display for MemoryUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "unionfind:      {} bytes", self.unionfind)?;
+        writeln!(f, "hashcons:       {} bytes", self.hashcons)?;
+        writeln!(f, "syn_hashcons:   {} bytes", self.syn_hashcons)?;
+        writeln!(f, "classes:        {} bytes", self.classes)?;
+        writeln!(f, "groups:         {} bytes", self.groups)?;
+        writeln!(f, "pending:        {} bytes", self.pending)?;
+        writeln!(f, "proof registry: {} bytes", self.proof_registry)?;
+        write!(f, "total:          {} bytes", self.total())
+    }
+}
+
+impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Estimates the memory held by this e-graph, see [MemoryUsage].
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let unionfind = {
+            let uf = self.unionfind.read().unwrap();
+            uf.capacity() * size_of::<ProvenAppliedId>() + uf.iter().map(|pai| pai.elem.m.heap_size()).sum::<usize>()
+        };
+
+        let mut classes = map_size(&self.classes);
+        let mut groups = 0;
+        for c in self.classes.values() {
+            classes += map_size(&c.nodes) + c.nodes.iter().map(|(sh, psn)| enode_size(sh) + psn.elem.heap_size()).sum::<usize>();
+            classes += set_size(&c.slots);
+            classes += set_size(&c.usages) + c.usages.iter().map(enode_size).sum::<usize>();
+            classes += enode_size(&c.syn_enode);
+            groups += c.group.heap_size(&|p| p.elem.heap_size());
+        }
+
+        #[cfg(feature = "explanations")]
+        let proof_registry = self.proof_registry.heap_size();
+        #[cfg(not(feature = "explanations"))]
+        let proof_registry = 0;
+
+        MemoryUsage {
+            unionfind,
+            hashcons: map_size(&self.hashcons) + self.hashcons.keys().map(enode_size).sum::<usize>(),
+            syn_hashcons: map_size(&self.syn_hashcons) + self.syn_hashcons.iter().map(|(sh, x)| enode_size(sh) + x.m.heap_size()).sum::<usize>(),
+            classes,
+            groups,
+            pending: map_size(&self.pending) + self.pending.keys().map(enode_size).sum::<usize>(),
+            proof_registry,
+        }
+    }
+}
+
+// The bytes allocated by the table of a hash map, assuming one control byte per bucket.
+fn map_size<K, V>(m: &HashMap<K, V>) -> usize {
+    m.capacity() * (size_of::<(K, V)>() + 1)
+}
+
+fn set_size<T>(s: &HashSet<T>) -> usize {
+    s.capacity() * (size_of::<T>() + 1)
+}
+
+// The bytes allocated on the heap by the slot maps within `n`.
+fn enode_size<L: Language>(n: &L) -> usize {
+    n.applied_id_occurences().iter().map(|x| x.m.heap_size()).sum()
+}
@@ -18,7 +18,22 @@ pub use check::*;
 mod analysis;
 pub use analysis::*;
 
-use std::cell::RefCell;
+mod remove;
+pub use remove::*;
+
+mod compact;
+pub use compact::*;
+
+mod memory;
+pub use memory::*;
+
+mod symmetries;
+pub use symmetries::*;
+
+mod facts;
+pub use facts::*;
+
+use std::sync::RwLock;
 
 // invariants:
 // 1. If two ENodes (that are in the EGraph) have equal .shape(), they have to be in the same eclass.
@@ -34,8 +49,9 @@ pub struct EGraph<L: Language, N: Analysis<L> = ()> {
     // normalizes the eclass.
     // Each Id i that is an output of the unionfind itself has unionfind[i] = (i, identity()).
 
-    // We use RefCell to allow for inter mutability, so that find(&self) can do path compression.
-    unionfind: RefCell<Vec<ProvenAppliedId>>,
+    // We use RwLock to allow for inter mutability, so that find(&self) can do path compression.
+    // A RwLock instead of a RefCell makes the read-only methods usable from multiple threads.
+    unionfind: RwLock<Vec<ProvenAppliedId>>,
 
     // if a class does't have unionfind[x].id = x, then it doesn't contain nodes / usages.
     // It's "shallow" if you will.
@@ -51,6 +67,13 @@ pub struct EGraph<L: Language, N: Analysis<L> = ()> {
     // E-Nodes that need to be re-processed, stored as shapes.
     pending: HashMap<L, PendingType>,
 
+    // The ids removed by prune. They are neither alive nor dead.
+    // Without explanations, they don't have an entry in `classes` anymore.
+    pruned: HashSet<Id>,
+
+    // The facts registered by add_fact, grouped by their e-class. Each key is the leader of its e-class.
+    facts: HashMap<Id, Vec<(AppliedId, String)>>,
+
     // TODO remove this if explanations are disabled.
     pub(crate) proof_registry: ProofRegistry,
 
@@ -103,11 +126,44 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
             hashcons: Default::default(),
             syn_hashcons: Default::default(),
             pending: Default::default(),
+            pruned: Default::default(),
+            facts: Default::default(),
             proof_registry: ProofRegistry::default(),
             subst_method: Some(S::new_boxed()),
         }
     }
 
+    /// Creates an independent copy of this e-graph, like [Clone::clone].
+    ///
+    /// In contrast to `clone`, the proofs collected so far are not copied, but shared between both e-graphs.
+    /// Prefer this over `clone` for large e-graphs with explanations enabled.
+    pub fn fork(&self) -> Self {
+        self.clone_with_registry(ghost!(self.proof_registry.fork()))
+    }
+
+    fn clone_with_registry(&self, proof_registry: ProofRegistry) -> Self {
+        #[allow(unused_mut)]
+        let mut classes = self.classes.clone();
+
+        // The ProvenPerms in the groups need to talk to the new registry.
+        #[cfg(feature = "explanations")]
+        for c in classes.values_mut() {
+            c.group.for_each_perm_mut(&mut |pp| pp.reg = proof_registry.clone());
+        }
+
+        EGraph {
+            unionfind: RwLock::new(self.unionfind.read().unwrap().clone()),
+            classes,
+            hashcons: self.hashcons.clone(),
+            syn_hashcons: self.syn_hashcons.clone(),
+            pending: self.pending.clone(),
+            pruned: self.pruned.clone(),
+            facts: self.facts.clone(),
+            proof_registry,
+            subst_method: self.subst_method.as_ref().map(|x| x.box_clone()),
+        }
+    }
+
     pub fn slots(&self, id: Id) -> HashSet<Slot> {
         self.classes[&id].slots.clone()
     }
@@ -165,6 +221,8 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
     }
 
     /// Checks that two AppliedIds are semantically equal.
+    ///
+    /// Unions are taken into account immediately, but equalities implied by congruence only after [EGraph::rebuild].
     pub fn eq(&self, a: &AppliedId, b: &AppliedId) -> bool {
         let a = self.find_applied_id(a);
         let b = self.find_applied_id(b);
@@ -379,6 +437,12 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
     }
 }
 
+impl<L: Language, N: Analysis<L>> Clone for EGraph<L, N> {
+    fn clone(&self) -> Self {
+        self.clone_with_registry(ghost!(self.proof_registry.deep_clone()))
+    }
+}
+
 impl PendingType {
     pub(crate) fn merge(self, other: PendingType) -> PendingType {
         match (self, other) {
@@ -112,8 +112,15 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         self.touched_class(from.id, PendingType::Full);
     }
 
+    /// Restores the e-graph invariants after a sequence of unions.
+    ///
+    /// Unions only mark the affected e-nodes as pending, so that many unions can share a single rebuild.
+    /// Until this is called, [EGraph::lookup] and e-matching might miss equivalences implied by congruence.
+    /// [EGraph::eq] and [EGraph::find_applied_id] are always up to date.
+    ///
+    /// [apply_rewrites] calls this once after applying all rewrites.
     #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
-    pub(crate) fn rebuild(&mut self) {
+    pub fn rebuild(&mut self) {
         if CHECKS { self.check(); }
         while let Some(sh) = self.pending.keys().cloned().next() {
             let pending_ty = self.pending.remove(&sh).unwrap();
@@ -123,7 +130,12 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         }
     }
 
-    fn handle_pending(&mut self, sh: L, pending_ty: PendingType) {
+    /// Whether there are no pending e-nodes, i.e. whether [EGraph::rebuild] would be a no-op.
+    pub fn is_clean(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub(in crate::egraph) fn handle_pending(&mut self, sh: L, pending_ty: PendingType) {
         let i = self.hashcons[&sh];
 
         /*
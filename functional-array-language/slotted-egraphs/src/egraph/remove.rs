@@ -0,0 +1,108 @@
+use crate::*;
+
+impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Removes the e-node `enode` from the e-graph.
+    ///
+    /// Returns false, if `enode` is not contained in the e-graph.
+    ///
+    /// Equalities that were derived using `enode` stay in the e-graph,
+    /// but the analysis data of its e-class (and of the e-classes using it) is recomputed from the remaining e-nodes.
+    /// An e-class whose last e-node is removed stays alive, but can't be matched or extracted anymore.
+    ///
+    /// The e-graph is rebuilt before removing `enode`, see [EGraph::rebuild].
+    pub fn remove(&mut self, enode: &L) -> bool {
+        self.rebuild();
+
+        let (sh, _) = self.shape(enode);
+        let Some(i) = self.hashcons.get(&sh).copied() else { return false };
+
+        let psn = self.raw_remove_from_class(i, sh);
+
+        // Adding the same term syntactically should not return the old e-class anymore,
+        // as it doesn't contain the e-node anymore.
+        self.remove_syn_hashcons_entry(psn.src_id);
+
+        self.recompute_analysis(i);
+
+        if CHECKS { self.check(); }
+
+        true
+    }
+
+    /// Removes all e-classes that are not reachable from any of the `roots`.
+    ///
+    /// Returns the number of removed e-classes.
+    ///
+    /// The `roots` stay valid, whereas [Id]s of removed e-classes may not be used anymore.
+    /// The analysis data of the remaining e-classes is unaffected, as they can't refer to removed e-classes.
+    ///
+    /// The e-graph is rebuilt before pruning, see [EGraph::rebuild].
+    pub fn prune(&mut self, roots: &[Id]) -> usize {
+        self.rebuild();
+
+        let mut reachable: HashSet<Id> = HashSet::default();
+        let mut todo: Vec<Id> = roots.iter().map(|x| self.find_id(*x)).collect();
+        while let Some(i) = todo.pop() {
+            if !reachable.insert(i) { continue; }
+            for sh in self.classes[&i].nodes.keys() {
+                todo.extend(sh.ids());
+            }
+        }
+
+        let unreachable: Vec<Id> = self.ids().into_iter().filter(|x| !reachable.contains(x)).collect();
+        for i in &unreachable {
+            for sh in self.classes[i].nodes.keys().cloned().collect::<Vec<_>>() {
+                self.raw_remove_from_class(*i, sh);
+            }
+        }
+
+        // Also the ids that were merged into a removed e-class get removed.
+        let removed: HashSet<Id> = self.unionfind_iter()
+                                       .filter(|(x, y)| !reachable.contains(&y.id) && !self.is_pruned(*x))
+                                       .map(|(x, _)| x)
+                                       .collect();
+        for i in &removed {
+            self.remove_syn_hashcons_entry(*i);
+            self.facts.remove(i);
+
+            // Explanations can still talk about the removed e-classes, so we keep their syntactic data.
+            #[cfg(not(feature = "explanations"))]
+            self.classes.remove(i);
+        }
+        self.pruned.extend(removed);
+
+        if CHECKS { self.check(); }
+
+        unreachable.len()
+    }
+
+    /// Whether the e-class `i` was removed by [EGraph::prune].
+    pub fn is_pruned(&self, i: Id) -> bool {
+        self.pruned.contains(&i)
+    }
+
+    // Removes the syn_hashcons entry pointing to the syn_enode of `i`.
+    fn remove_syn_hashcons_entry(&mut self, i: Id) {
+        let (sh, _) = self.classes[&i].syn_enode.weak_shape();
+        if self.syn_hashcons.get(&sh).is_some_and(|x| x.id == i) {
+            self.syn_hashcons.remove(&sh);
+        }
+    }
+
+    // Recomputes the analysis data of `i` from its e-nodes, and updates the e-classes using it accordingly.
+    // In contrast to `update_analysis`, this can make the analysis data less precise.
+    fn recompute_analysis(&mut self, i: Id) {
+        let mut todo = vec![i];
+        while let Some(i) = todo.pop() {
+            let c = &self.classes[&i];
+            let Some(new) = c.nodes.keys().map(|sh| N::make(self, sh)).reduce(N::merge) else { continue };
+            if new == c.analysis_data { continue; }
+
+            let usages: Vec<L> = c.usages.iter().cloned().collect();
+            self.classes.get_mut(&i).unwrap().analysis_data = new;
+            for sh in usages {
+                todo.push(self.hashcons[&sh]);
+            }
+        }
+    }
+}
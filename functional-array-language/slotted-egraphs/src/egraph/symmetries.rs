@@ -0,0 +1,83 @@
+use crate::*;
+
+/// The symmetries of an e-class, see [EGraph::symmetries].
+///
+/// A symmetry is a permutation of the slots of an e-class that maps the e-class to itself.
+/// All permutations are expressed in terms of the slots of the [AppliedId] passed to [EGraph::symmetries].
+#[derive(Clone, Debug)]
+pub struct Symmetries {
+    /// The slots that are permuted, sorted.
+    /// Redundant slots of the [AppliedId] are not contained.
+    pub slots: Vec<Slot>,
+
+    /// A set of permutations that generates the symmetry group, sorted.
+    /// The identity is not contained.
+    pub generators: Vec<SlotMap>,
+
+    /// The number of symmetries, including the identity.
+    pub order: usize,
+
+    /// The orbits of the slots, i.e. the slots that can be mapped onto each other.
+    /// Each orbit is sorted, and the orbits are sorted by their smallest slot.
+    pub orbits: Vec<Vec<Slot>>,
+
+    group: Group<Perm>,
+}
+
+impl Symmetries {
+    /// Whether `perm` is a symmetry, i.e. a permutation of the slots that is contained in the symmetry group.
+    pub fn contains(&self, perm: &SlotMap) -> bool {
+        perm.is_perm() && perm.keys_vec() == self.slots && self.group.contains(perm)
+    }
+
+    /// Whether the symmetry group is exactly the group generated by `perms`.
+    pub fn is_generated_by(&self, perms: &[SlotMap]) -> bool {
+        if !perms.iter().all(|p| self.contains(p)) { return false; }
+
+        let identity = SlotMap::identity(&self.slots.iter().copied().collect());
+        let group = Group::new(&identity, perms.iter().cloned().collect());
+        group.count() == self.order
+    }
+}
+
+impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Returns the symmetries of the e-class of `i`.
+    ///
+    /// Two [AppliedId]s `i` and `i.apply_slotmap(perm)` are equal according to [EGraph::eq], if and only if `perm` is a symmetry of `i`.
+    pub fn symmetries(&self, i: &AppliedId) -> Symmetries {
+        let i = self.find_applied_id(i);
+        let grp = &self.classes[&i.id].group;
+
+        // i.m :: slots(i.id) -> X
+        // p :: slots(i.id) -> slots(i.id)
+        // out :: X -> X
+        let m_inv = i.m.inverse();
+        let rename = |p: &Perm| m_inv.compose(p).compose(&i.m);
+
+        let mut slots = i.m.values_vec();
+        slots.sort();
+
+        let mut generators: Vec<SlotMap> = grp.generators().iter().map(|pp| rename(&pp.elem)).collect();
+        generators.sort();
+
+        let mut orbits: Vec<Vec<Slot>> = Vec::new();
+        for s in &slots {
+            if orbits.iter().any(|o| o.contains(s)) { continue; }
+
+            let mut orbit: Vec<Slot> = grp.orbit(m_inv[*s]).into_iter().map(|x| i.m[x]).collect();
+            orbit.sort();
+            orbits.push(orbit);
+        }
+
+        let identity = SlotMap::identity(&slots.iter().copied().collect());
+        let group = Group::new(&identity, generators.iter().cloned().collect());
+
+        Symmetries {
+            slots,
+            generators,
+            order: grp.count(),
+            orbits,
+            group,
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::*;
 
 impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Unions the classes of `l` and `r`.
+    ///
+    /// The e-graph is not rebuilt afterwards, see [EGraph::rebuild].
     pub fn union(&mut self, l: &AppliedId, r: &AppliedId) -> bool {
         self.union_justified(l, r, None)
     }
@@ -15,6 +18,10 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         self.union_instantiations(&a, &b, &subst, j)
     }
 
+    /// Unions the instantiations of `from_pat` and `to_pat` under `subst`.
+    ///
+    /// Just like [EGraph::union], this only marks the affected e-nodes as pending.
+    /// Congruences are restored by the next call to [EGraph::rebuild].
     #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
     pub fn union_instantiations(&mut self, from_pat: &Pattern<L>, to_pat: &Pattern<L>, subst: &Subst, justification: Option<String>) -> bool {
         let a = pattern_subst(self, from_pat, subst);
@@ -25,9 +32,7 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
 
         let proof = ghost!(self.prove_explicit(&syn_a, &syn_b, justification));
 
-        let out = self.union_internal(&a, &b, proof);
-        self.rebuild();
-        out
+        self.union_internal(&a, &b, proof)
     }
 
     #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
@@ -184,6 +189,7 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
             proof,
         };
         self.unionfind_set(from.id, pai);
+        self.move_facts(from.id);
 
         // who updates the usages? raw_add_to_class & raw_remove_from_class do that.
 
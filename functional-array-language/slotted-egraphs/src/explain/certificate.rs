@@ -0,0 +1,496 @@
+use crate::*;
+
+use std::fmt::{self, Display, Formatter};
+
+/// A machine-checkable proof of an equation `start = end`.
+///
+/// It consists of a sequence of rewrite steps, each of which can be replayed using [ProofCertificate::check].
+/// Checking a certificate only requires the rewrite rules, but no e-graph.
+///
+/// A certificate can be converted to text using [Display], and read back using [ProofCertificate::parse].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofCertificate<L: Language> {
+    pub start: RecExpr<L>,
+    pub steps: Vec<CertificateStep<L>>,
+}
+
+/// A single step of a [ProofCertificate].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertificateStep<L: Language> {
+    /// The name of the applied [Rule].
+    pub rule: String,
+
+    /// Whether the rule was applied from right to left.
+    pub backward: bool,
+
+    /// The position of the rewritten subterm, as a path of child indices from the root.
+    pub pos: Vec<usize>,
+
+    /// The terms for the pattern variables of the rule, sorted by name.
+    pub subst: Vec<(String, RecExpr<L>)>,
+
+    /// Renames the slots of the rule to the slots used in the terms.
+    pub slots: SlotMap,
+
+    /// The whole term after this step.
+    pub dst: RecExpr<L>,
+}
+
+/// The reasons for why a [ProofCertificate] can be rejected.
+///
+/// `step` is the (zero-based) index of the offending step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CertificateError {
+    /// The step was not justified by any rule.
+    MissingRule { step: usize },
+
+    /// The step uses a rule that is not part of the rule set.
+    UnknownRule { step: usize, rule: String },
+
+    /// The position of the step doesn't exist in the term.
+    InvalidPosition { step: usize },
+
+    /// The substitution of the step doesn't cover a pattern variable of the rule.
+    UnboundPatternVar { step: usize, var: String },
+
+    /// The slot renaming of the step doesn't cover a slot of the rule.
+    UnboundSlot { step: usize, slot: Slot },
+
+    /// The instantiated rule doesn't match the subterm at the position of the step.
+    NoMatch { step: usize },
+
+    /// The term after the step is not the one that the instantiated rule produces.
+    WrongResult { step: usize },
+
+    /// The certificate proves a different equation than the one that was asked for.
+    WrongEquation,
+
+    /// The textual representation of the certificate is malformed.
+    Parse { line: usize, msg: String },
+}
+
+impl Display for CertificateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateError::MissingRule { step } => write!(f, "step {step} is not justified by a rule"),
+            CertificateError::UnknownRule { step, rule } => write!(f, "step {step} uses unknown rule '{rule}'"),
+            CertificateError::InvalidPosition { step } => write!(f, "step {step} rewrites at a position that doesn't exist"),
+            CertificateError::UnboundPatternVar { step, var } => write!(f, "step {step} doesn't instantiate ?{var}"),
+            CertificateError::UnboundSlot { step, slot } => write!(f, "step {step} doesn't rename {slot}"),
+            CertificateError::NoMatch { step } => write!(f, "the rule of step {step} doesn't match the rewritten subterm"),
+            CertificateError::WrongResult { step } => write!(f, "step {step} doesn't produce the term it claims"),
+            CertificateError::WrongEquation => write!(f, "the certificate proves a different equation"),
+            CertificateError::Parse { line, msg } => write!(f, "line {line}: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl ProvenEqRaw {
+    /// Converts this explanation into a [ProofCertificate].
+    ///
+    /// Each rewrite step of the explanation needs to be justified by one of the `rules`, see [Rewrite::rule].
+    pub fn to_certificate<L: Language, N: Analysis<L>>(&self, graph: &EGraph<L, N>, rules: &[Rule<L>]) -> Result<ProofCertificate<L>, CertificateError> {
+        let start = graph.get_syn_expr(&self.l);
+
+        let mut ctx = FlatteningContext::new(start.clone());
+        let flat_steps = Self::to_steps(graph, self, vec![], false, &mut ctx);
+
+        let mut steps = Vec::new();
+        let mut cur = start.clone();
+        for (i, flat) in flat_steps.into_iter().enumerate() {
+            let name = flat.jus.ok_or(CertificateError::MissingRule { step: i })?;
+            let rule = find_rule(rules, &name, i)?;
+            let pos: Vec<usize> = flat.rw_pos.iter().map(|x| *x as usize).collect();
+
+            let src = subexpr_at(&cur, &pos).ok_or(CertificateError::InvalidPosition { step: i })?;
+            let dst = subexpr_at(&flat.dst, &pos).ok_or(CertificateError::InvalidPosition { step: i })?;
+
+            // The lhs of the rule describes the source of a forward step, and the destination of a backward step.
+            let lhs_term = if flat.back { dst } else { src };
+            let mut subst = HashMap::default();
+            let mut slots = SlotMap::new();
+            if !match_pattern(&rule.lhs, lhs_term, &mut subst, &mut slots) {
+                return Err(CertificateError::NoMatch { step: i });
+            }
+
+            // Slots that only occur on the rhs are bound within it, so any fresh name will do.
+            for s in pattern_slots(&rule.rhs) {
+                if !slots.contains_key(s) {
+                    slots.insert(s, Slot::fresh());
+                }
+            }
+
+            let mut subst: Vec<_> = subst.into_iter().collect();
+            subst.sort_by(|(x, _), (y, _)| x.cmp(y));
+
+            cur = flat.dst.clone();
+            steps.push(CertificateStep {
+                rule: name,
+                backward: flat.back,
+                pos,
+                subst,
+                slots,
+                dst: flat.dst,
+            });
+        }
+
+        Ok(ProofCertificate { start, steps })
+    }
+}
+
+impl<L: Language> ProofCertificate<L> {
+    /// The term that this certificate ends with.
+    pub fn end(&self) -> &RecExpr<L> {
+        self.steps.last().map(|x| &x.dst).unwrap_or(&self.start)
+    }
+
+    /// Replays each step of the certificate against the `rules`.
+    ///
+    /// Terms are compared up to renaming of bound slots.
+    /// Conditions of conditional rewrites are not checked.
+    pub fn check(&self, rules: &[Rule<L>]) -> Result<(), CertificateError> {
+        let mut cur = &self.start;
+        for (i, step) in self.steps.iter().enumerate() {
+            let rule = find_rule(rules, &step.rule, i)?;
+            let (from, to) = if step.backward { (&rule.rhs, &rule.lhs) } else { (&rule.lhs, &rule.rhs) };
+
+            let src = subexpr_at(cur, &step.pos).ok_or(CertificateError::InvalidPosition { step: i })?;
+            if !alpha_eq(&instantiate(from, step, i)?, src) || !constraints_hold(&rule.lhs, step) {
+                return Err(CertificateError::NoMatch { step: i });
+            }
+
+            let expected = replace_subexpr_at(cur, &step.pos, instantiate(to, step, i)?);
+            if !alpha_eq(&expected, &step.dst) {
+                return Err(CertificateError::WrongResult { step: i });
+            }
+
+            cur = &step.dst;
+        }
+        Ok(())
+    }
+
+    /// Checks that this certificate is valid, and that it proves `l = r`.
+    ///
+    /// The free slots of a certificate can be renamed consistently, as explanations only talk about equations up to renaming.
+    pub fn check_equation(&self, l: &RecExpr<L>, r: &RecExpr<L>, rules: &[Rule<L>]) -> Result<(), CertificateError> {
+        self.check(rules)?;
+
+        let mut free = SlotMap::new();
+        let ok = alpha_eq_impl(&self.start, l, &mut Vec::new(), Some(&mut free))
+              && alpha_eq_impl(self.end(), r, &mut Vec::new(), Some(&mut free));
+        if !ok {
+            return Err(CertificateError::WrongEquation);
+        }
+        Ok(())
+    }
+
+    /// Parses a certificate in the format produced by its [Display] implementation.
+    pub fn parse(s: &str) -> Result<Self, CertificateError> {
+        let mut start = None;
+        let mut steps: Vec<CertificateStep<L>> = Vec::new();
+        let mut has_dst = true;
+
+        for (i, line) in s.lines().enumerate() {
+            let line_nr = i + 1;
+            let err = |msg: String| CertificateError::Parse { line: line_nr, msg };
+            let term = |s: &str| RecExpr::parse(s).map_err(|e| err(format!("invalid term: {e}")));
+
+            let line = line.trim();
+            if line.is_empty() { continue; }
+
+            let (key, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let rest = rest.trim();
+            match key {
+                "start" if start.is_none() => start = Some(term(rest)?),
+                _ if start.is_none() => return Err(err("expected 'start'".to_string())),
+                "step" => {
+                    if !has_dst { return Err(err("expected 'dst' of the previous step".to_string())); }
+
+                    let mut parts = rest.splitn(3, ' ');
+                    let backward = match parts.next() {
+                        Some("=>") => false,
+                        Some("<=") => true,
+                        _ => return Err(err("expected '=>' or '<='".to_string())),
+                    };
+                    let pos = parts.next().and_then(parse_pos).ok_or_else(|| err("expected a position like '@0.1'".to_string()))?;
+                    let rule = parts.next().unwrap_or("").trim().to_string();
+                    if rule.is_empty() { return Err(err("expected a rule name".to_string())); }
+
+                    steps.push(CertificateStep {
+                        rule,
+                        backward,
+                        pos,
+                        subst: Vec::new(),
+                        slots: SlotMap::new(),
+                        dst: start.clone().unwrap(),
+                    });
+                    has_dst = false;
+                },
+                "subst" | "slot" | "dst" => {
+                    let Some(step) = steps.last_mut() else { return Err(err(format!("'{key}' outside of a step"))) };
+                    if has_dst { return Err(err(format!("'{key}' after the 'dst' of a step"))); }
+
+                    let (x, y) = rest.split_once(' ').unwrap_or((rest, ""));
+                    match key {
+                        "subst" => {
+                            let var = x.strip_prefix('?').ok_or_else(|| err("expected a pattern variable".to_string()))?;
+                            step.subst.push((var.to_string(), term(y)?));
+                        },
+                        "slot" => {
+                            let x = parse_slot(x).ok_or_else(|| err("expected a slot".to_string()))?;
+                            let y = parse_slot(y.trim()).ok_or_else(|| err("expected a slot".to_string()))?;
+                            step.slots.insert(x, y);
+                        },
+                        _ => {
+                            step.dst = term(rest)?;
+                            has_dst = true;
+                        },
+                    }
+                },
+                _ => return Err(err(format!("unexpected '{key}'"))),
+            }
+        }
+
+        let n = s.lines().count();
+        let Some(start) = start else { return Err(CertificateError::Parse { line: n, msg: "expected 'start'".to_string() }) };
+        if !has_dst { return Err(CertificateError::Parse { line: n, msg: "expected 'dst' of the last step".to_string() }); }
+
+        Ok(ProofCertificate { start, steps })
+    }
+}
+
+impl<L: Language> Display for ProofCertificate<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "start {}", self.start)?;
+        for step in &self.steps {
+            let dir = if step.backward { "<=" } else { "=>" };
+            let pos: Vec<String> = step.pos.iter().map(|x| x.to_string()).collect();
+            writeln!(f, "step {dir} @{} {}", pos.join("."), step.rule)?;
+            for (x, t) in &step.subst {
+                writeln!(f, "subst ?{x} {t}")?;
+            }
+            for (x, y) in step.slots.iter() {
+                writeln!(f, "slot {x} {y}")?;
+            }
+            writeln!(f, "dst {}", step.dst)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_pos(s: &str) -> Option<Vec<usize>> {
+    let s = s.strip_prefix('@')?;
+    if s.is_empty() { return Some(Vec::new()); }
+    s.split('.').map(|x| x.parse().ok()).collect()
+}
+
+fn parse_slot(s: &str) -> Option<Slot> {
+    let s = s.strip_prefix('$')?;
+    if s.is_empty() { return None; }
+    Some(Slot::named(s))
+}
+
+fn find_rule<'a, L: Language>(rules: &'a [Rule<L>], name: &str, step: usize) -> Result<&'a Rule<L>, CertificateError> {
+    rules.iter()
+         .find(|r| r.name == name)
+         .ok_or_else(|| CertificateError::UnknownRule { step, rule: name.to_string() })
+}
+
+fn subexpr_at<'a, L: Language>(re: &'a RecExpr<L>, pos: &[usize]) -> Option<&'a RecExpr<L>> {
+    match pos.split_first() {
+        Some((i, rest)) => subexpr_at(re.children.get(*i)?, rest),
+        None => Some(re),
+    }
+}
+
+// Expects `pos` to be valid in `re`.
+fn replace_subexpr_at<L: Language>(re: &RecExpr<L>, pos: &[usize], t: RecExpr<L>) -> RecExpr<L> {
+    match pos.split_first() {
+        Some((i, rest)) => {
+            let mut re = re.clone();
+            re.children[*i] = replace_subexpr_at(&re.children[*i], rest, t);
+            re
+        },
+        None => t,
+    }
+}
+
+// Matches `pat` against `re`, where the pattern slots are mapped to the slots of `re` using `slots`.
+fn match_pattern<L: Language>(pat: &Pattern<L>, re: &RecExpr<L>, subst: &mut HashMap<String, RecExpr<L>>, slots: &mut SlotMap) -> bool {
+    match pat {
+        Pattern::PVar(v) | Pattern::ConstrainedPVar(v, _) => {
+            if let Some(t) = subst.get(v) {
+                return alpha_eq(t, re);
+            }
+            subst.insert(v.clone(), re.clone());
+            true
+        },
+        Pattern::ENode(n, children) => {
+            let (p_op, p_children) = n.to_op();
+            let (r_op, r_children) = re.node.to_op();
+            if p_op != r_op || p_children.len() != r_children.len() || children.len() != re.children.len() {
+                return false;
+            }
+
+            for (x, y) in p_children.iter().zip(r_children.iter()) {
+                match (x, y) {
+                    (Child::Slot(x), Child::Slot(y)) => {
+                        if let Some(y2) = slots.get(*x) {
+                            if y2 != *y { return false; }
+                        } else if slots.values().contains(y) {
+                            return false;
+                        } else {
+                            slots.insert(*x, *y);
+                        }
+                    },
+                    (Child::AppliedId(_), Child::AppliedId(_)) => {},
+                    _ => return false,
+                }
+            }
+
+            children.iter()
+                    .zip(re.children.iter())
+                    .all(|(p, r)| match_pattern(p, r, subst, slots))
+        },
+        Pattern::Subst(..) => false,
+    }
+}
+
+// Checks the slot constraints of `pat` against the pattern-variables and slots of `step`.
+fn constraints_hold<L: Language>(pat: &Pattern<L>, step: &CertificateStep<L>) -> bool {
+    slot_constraints(pat).into_iter().all(|(v, c)| {
+        let Some((_, t)) = step.subst.iter().find(|(x, _)| x == v) else { return false };
+        free_slots(t).into_iter().all(|s| {
+            // the pattern slots that `s` could stand for.
+            let mut ps = step.slots.iter().filter(|(_, y)| *y == s).map(|(x, _)| x).peekable();
+            if ps.peek().is_none() { c.allows(None) } else { ps.all(|p| c.allows(Some(p))) }
+        })
+    })
+}
+
+fn pattern_slots<L: Language>(pat: &Pattern<L>) -> HashSet<Slot> {
+    match pat {
+        Pattern::PVar(_) | Pattern::ConstrainedPVar(..) => HashSet::default(),
+        Pattern::ENode(n, children) => {
+            let mut out: HashSet<Slot> = n.all_slot_occurences().into_iter().collect();
+            for c in children {
+                out.extend(pattern_slots(c));
+            }
+            out
+        },
+        Pattern::Subst(b, x, t) => &(&pattern_slots(b) | &pattern_slots(x)) | &pattern_slots(t),
+    }
+}
+
+fn instantiate<L: Language>(pat: &Pattern<L>, step: &CertificateStep<L>, i: usize) -> Result<RecExpr<L>, CertificateError> {
+    match pat {
+        Pattern::PVar(v) | Pattern::ConstrainedPVar(v, _) => {
+            step.subst.iter()
+                .find(|(x, _)| x == v)
+                .map(|(_, t)| t.clone())
+                .ok_or_else(|| CertificateError::UnboundPatternVar { step: i, var: v.clone() })
+        },
+        Pattern::ENode(n, children) => {
+            let mut node = n.clone();
+            for s in node.all_slot_occurences_mut() {
+                *s = step.slots.get(*s).ok_or(CertificateError::UnboundSlot { step: i, slot: *s })?;
+            }
+            let children = children.iter()
+                                   .map(|c| instantiate(c, step, i))
+                                   .collect::<Result<_, _>>()?;
+            Ok(RecExpr { node, children })
+        },
+        Pattern::Subst(b, x, t) => {
+            let b = instantiate(b, step, i)?;
+            let x = instantiate(x, step, i)?;
+            let t = instantiate(t, step, i)?;
+            let avoid = &free_slots(&x) | &free_slots(&t);
+            Ok(term_subst(&b, &x, &t, &avoid))
+        },
+    }
+}
+
+fn free_slots<L: Language>(re: &RecExpr<L>) -> HashSet<Slot> {
+    let mut out: HashSet<Slot> = re.node.public_slot_occurences().into_iter().collect();
+    for c in &re.children {
+        out.extend(free_slots(c));
+    }
+    for s in re.node.private_slot_occurences() {
+        out.remove(&s);
+    }
+    out
+}
+
+// computes re[x := t], while renaming the binders that would capture a slot of `x` or `t`.
+fn term_subst<L: Language>(re: &RecExpr<L>, x: &RecExpr<L>, t: &RecExpr<L>, avoid: &HashSet<Slot>) -> RecExpr<L> {
+    if alpha_eq(re, x) {
+        return t.clone();
+    }
+
+    let mut re = re.clone();
+    for s in re.node.private_slot_occurences() {
+        if avoid.contains(&s) {
+            rename_slot(&mut re, s, Slot::fresh());
+        }
+    }
+
+    let children = re.children.iter().map(|c| term_subst(c, x, t, avoid)).collect();
+    RecExpr { node: re.node, children }
+}
+
+fn rename_slot<L: Language>(re: &mut RecExpr<L>, from: Slot, to: Slot) {
+    for s in re.node.all_slot_occurences_mut() {
+        if *s == from { *s = to; }
+    }
+    for c in re.children.iter_mut() {
+        rename_slot(c, from, to);
+    }
+}
+
+/// Checks whether two terms are equal up to renaming of bound slots.
+pub fn alpha_eq<L: Language>(a: &RecExpr<L>, b: &RecExpr<L>) -> bool {
+    alpha_eq_impl(a, b, &mut Vec::new(), None)
+}
+
+// `bound` contains the pairs of corresponding bound slots, the innermost binder last.
+// If `free` is given, free slots may be renamed by the bijection `free`, which is extended on the fly.
+// Otherwise, free slots have to be equal.
+fn alpha_eq_impl<L: Language>(a: &RecExpr<L>, b: &RecExpr<L>, bound: &mut Vec<(Slot, Slot)>, mut free: Option<&mut SlotMap>) -> bool {
+    let (a_op, a_children) = a.node.to_op();
+    let (b_op, b_children) = b.node.to_op();
+    if a_op != b_op || a_children.len() != b_children.len() || a.children.len() != b.children.len() {
+        return false;
+    }
+
+    let a_private = a.node.private_slot_occurences();
+    let b_private = b.node.private_slot_occurences();
+    if a_private.len() != b_private.len() {
+        return false;
+    }
+
+    let n = bound.len();
+    bound.extend(a_private.into_iter().zip(b_private));
+
+    let ok = a_children.iter().zip(b_children.iter()).all(|(x, y)| match (x, y) {
+        (Child::Slot(x), Child::Slot(y)) => {
+            match bound.iter().rev().find(|(l, r)| l == x || r == y) {
+                Some((l, r)) => l == x && r == y,
+                None => match free.as_deref_mut() {
+                    None => x == y,
+                    Some(free) => match free.get(*x) {
+                        Some(y2) => y2 == *y,
+                        None if free.values().contains(y) => false,
+                        None => { free.insert(*x, *y); true },
+                    },
+                },
+            }
+        },
+        (Child::AppliedId(_), Child::AppliedId(_)) => true,
+        _ => false,
+    }) && a.children.iter().zip(b.children.iter()).all(|(x, y)| alpha_eq_impl(x, y, bound, free.as_deref_mut()));
+
+    bound.truncate(n);
+    ok
+}
@@ -1,17 +1,17 @@
 use crate::*;
 
-type Pos = Vec<u8>;
+pub(crate) type Pos = Vec<u8>;
 
-struct FlatteningContext<L: Language> {
-    head:     RecExpr<L>,
-    slot_map: HashMap<Slot, Slot>
+pub(crate) struct FlatteningContext<L: Language> {
+    pub(crate) head:     RecExpr<L>,
+    pub(crate) slot_map: HashMap<Slot, Slot>
 }
 
-struct Step<L: Language> {
-    dst:    RecExpr<L>,
-    rw_pos: Pos,
-    jus:    String,
-    back:   bool
+pub(crate) struct Step<L: Language> {
+    pub(crate) dst:    RecExpr<L>,
+    pub(crate) rw_pos: Pos,
+    pub(crate) jus:    Option<String>,
+    pub(crate) back:   bool
 }
 
 impl<L: Language> Step<L> {
@@ -49,18 +49,64 @@ impl<L: Language> Step<L> {
             format!("({})", str)
         } else {
             let dir_str = if self.back { "<=" } else { "=>" };
-            format!("(Rewrite{} {} {})", dir_str, self.jus, self.dst)
+            let jus = self.jus.as_deref().unwrap_or("?");
+            format!("(Rewrite{} {} {})", dir_str, jus, self.dst)
         }
     }
 }
 
+/// A flattened explanation, i.e. a sequence of rewrite steps leading from `start` to the other side of the equation.
+///
+/// This is the structured counterpart of [ProvenEqRaw::to_flat_string].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlatExplanation<L: Language> {
+    pub start: RecExpr<L>,
+    pub steps: Vec<FlatStep<L>>,
+}
+
+/// A single step of a [FlatExplanation].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlatStep<L: Language> {
+    /// The justification of the rewrite, i.e. the name of the applied rule.
+    pub rule: Option<String>,
+
+    /// Whether the rule was applied from right to left.
+    pub backward: bool,
+
+    /// The position of the rewritten subterm, as a path of child indices from the root.
+    pub pos: Vec<usize>,
+
+    /// The whole term after this step.
+    pub dst: RecExpr<L>,
+}
+
 impl ProvenEqRaw {
 
+    /// Returns the flattened explanation as a sequence of rewrite steps, see [FlatExplanation].
+    pub fn to_flat_explanation<L: Language, N: Analysis<L>>(&self, graph: &EGraph<L, N>) -> FlatExplanation<L> {
+        let start = graph.get_syn_expr(&self.l);
+
+        let mut init_ctx = FlatteningContext::new(start.clone());
+        let steps = Self::to_steps(graph, self, vec![], false, &mut init_ctx)
+            .into_iter()
+            .map(|step| FlatStep {
+                rule: step.jus,
+                backward: step.back,
+                pos: step.rw_pos.iter().map(|x| *x as usize).collect(),
+                dst: step.dst,
+            })
+            .collect();
+
+        FlatExplanation { start, steps }
+    }
+
     /// Returns a string representation of a flattened explanation.
+    ///
+    /// Steps without a justification, e.g. from [EGraph::union], are shown with the rule name `?`.
     pub fn to_flat_string<L: Language, N: Analysis<L>>(&self, graph: &EGraph<L, N>) -> String {
         let start = graph.get_syn_expr(&self.l);
 
-        let mut init_ctx = FlatteningContext { head: start.clone(), slot_map: Default::default() };
+        let mut init_ctx = FlatteningContext::new(start.clone());
         let steps = Self::to_steps(graph, self, vec![], false, &mut init_ctx);     
         
         let mut result = start.to_string();
@@ -68,7 +114,7 @@ impl ProvenEqRaw {
         result
     }
 
-    fn to_steps<L: Language, N: Analysis<L>>(
+    pub(crate) fn to_steps<L: Language, N: Analysis<L>>(
         graph: &EGraph<L, N>, eq: &ProvenEqRaw, pos: Pos, symm: bool, ctx: &mut FlatteningContext<L>
     ) -> Vec<Step<L>> {
         let subsrc = eq.side(/*src:*/ true, symm, graph);
@@ -106,7 +152,7 @@ impl ProvenEqRaw {
                 subdst.apply_slot_map(&ctx.slot_map);
                 let dst = ctx.head.replace_subexpr(&pos, subdst);
                 let step = Step { 
-                    dst: dst.clone(), rw_pos: pos, jus: jus.as_ref().unwrap().clone(), back: symm 
+                    dst: dst.clone(), rw_pos: pos, jus: jus.clone(), back: symm 
                 };
                 ctx.head = dst;
                 vec![step]
@@ -128,6 +174,10 @@ impl ProvenEqRaw {
 
 impl<L: Language> FlatteningContext<L> {
 
+    pub(crate) fn new(head: RecExpr<L>) -> Self {
+        FlatteningContext { head, slot_map: Default::default() }
+    }
+
     fn update_slot_map(&mut self, subsrc: &RecExpr<L>, pos: &Pos) {
         let subhead = self.head.subexpr(pos);
         Self::update_slot_map_core(&mut self.slot_map, subhead, subsrc);
@@ -151,7 +201,8 @@ impl<L: Language> FlatteningContext<L> {
                     child_idx += 1;
                 },
                 (Child::Slot(h), Child::Slot(s)) => { 
-                    if h != s { map.insert(*s, *h); }
+                    // Identities are recorded too, as they override earlier renamings of `s`.
+                    map.insert(*s, *h);
                 },
                 _ => panic!("'FlatteningContext.update_slot_map_core' found distinct children.")
             }
@@ -189,12 +240,9 @@ impl<L: Language> RecExpr<L> {
         }
     }
 
-    // Important: This will loop if the slot map contains a cycle!
+    // The renaming is accumulated over all steps, so it may contain both `a -> b` and `b -> a`.
+    // Thus it must not be applied transitively.
     fn map_slot(s: Slot, m: &HashMap<Slot, Slot>) -> Slot {
-        if let Some(&new) = m.get(&s) {
-            Self::map_slot(new, m)
-        } else {
-            s
-        }
+        m.get(&s).copied().unwrap_or(s)
     }
 }
\ No newline at end of file
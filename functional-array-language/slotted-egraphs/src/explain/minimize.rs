@@ -0,0 +1,226 @@
+use crate::*;
+
+type PrfPtr = *const ProvenEqRaw;
+
+impl ProvenEqRaw {
+    /// The number of rewrite steps in this proof, i.e. the length of its flattened form.
+    ///
+    /// Subproofs that are used multiple times are counted multiple times.
+    pub fn size(&self) -> usize {
+        size_impl(self, &mut Default::default())
+    }
+}
+
+// Iterative, as proofs can be very deep.
+fn size_impl(prf: &ProvenEqRaw, memo: &mut HashMap<PrfPtr, usize>) -> usize {
+    let mut stack: Vec<&ProvenEqRaw> = vec![prf];
+
+    'outer: while let Some(x) = stack.last().cloned() {
+        let ptr = x as PrfPtr;
+        if memo.contains_key(&ptr) {
+            stack.pop();
+            continue;
+        }
+
+        let mut size = 0usize;
+        for sub in x.subproofs() {
+            match memo.get(&(&**sub as PrfPtr)) {
+                Some(s) => size = size.saturating_add(*s),
+                None => {
+                    stack.push(sub);
+                    continue 'outer;
+                },
+            }
+        }
+
+        if let Proof::Explicit(_) = x.proof() {
+            size = 1;
+        }
+
+        memo.insert(ptr, size);
+        stack.pop();
+    }
+
+    memo[&(prf as PrfPtr)]
+}
+
+impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Like [EGraph::explain_equivalence], but additionally tries to shorten the explanation using [EGraph::minimize_proof].
+    pub fn explain_equivalence_minimized(&mut self, t1: RecExpr<L>, t2: RecExpr<L>) -> ProvenEq {
+        let prf = self.explain_equivalence(t1, t2);
+        self.minimize_proof(&prf)
+    }
+
+    /// Searches for a smaller proof of the same equation.
+    ///
+    /// Transitivity chains are shortened greedily:
+    /// detours that return to an earlier term are removed, and parts of the chain are replaced by shorter proofs that the e-graph already knows.
+    ///
+    /// The result is never larger than `prf`, as measured by [ProvenEqRaw::size].
+    pub fn minimize_proof(&self, prf: &ProvenEq) -> ProvenEq {
+        let mut m = Minimizer {
+            eg: self,
+            reg: ProofRegistry::default(),
+            sizes: Default::default(),
+            done: Default::default(),
+        };
+        m.minimize(prf)
+    }
+}
+
+struct Minimizer<'a, L: Language, N: Analysis<L>> {
+    eg: &'a EGraph<L, N>,
+
+    // The proofs we build are registered here instead of the e-graph's registry.
+    // Otherwise, the registry would just return the original proofs again.
+    reg: ProofRegistry,
+
+    sizes: HashMap<PrfPtr, usize>,
+    done: HashMap<PrfPtr, ProvenEq>,
+}
+
+impl<'a, L: Language, N: Analysis<L>> Minimizer<'a, L, N> {
+    fn size(&mut self, prf: &ProvenEq) -> usize {
+        size_impl(prf, &mut self.sizes)
+    }
+
+    fn minimize(&mut self, prf: &ProvenEq) -> ProvenEq {
+        let ptr = &**prf as PrfPtr;
+        if let Some(x) = self.done.get(&ptr) {
+            return x.clone();
+        }
+
+        let out = match prf.proof() {
+            Proof::Explicit(_) | Proof::Reflexivity(_) => prf.clone(),
+            Proof::Congruence(CongruenceProof(xs)) => {
+                let ys: Vec<ProvenEq> = xs.iter().map(|x| self.minimize(x)).collect();
+                if xs.iter().zip(ys.iter()).all(|(x, y)| Arc::ptr_eq(x, y)) {
+                    prf.clone()
+                } else {
+                    CongruenceProof(ys).check_with_registry(prf, self.eg, &self.reg)
+                }
+            },
+            Proof::Symmetry(_) | Proof::Transitivity(_) => {
+                let links = self.links(prf);
+                match self.shorten_chain(links) {
+                    Some(c) if equations_match(&c, prf) && self.size(&c) <= self.size(prf) => c,
+                    _ => prf.clone(),
+                }
+            },
+        };
+
+        self.done.insert(ptr, out.clone());
+        out
+    }
+
+    // Flattens nested transitivity & symmetry proofs into a chain of minimized links.
+    fn links(&mut self, prf: &ProvenEq) -> Vec<ProvenEq> {
+        let mut out = Vec::new();
+
+        // (proof, whether it's used backwards)
+        let mut stack = vec![(prf.clone(), false)];
+        while let Some((x, rev)) = stack.pop() {
+            match x.proof() {
+                Proof::Transitivity(TransitivityProof(a, b)) => {
+                    if rev {
+                        stack.push((a.clone(), true));
+                        stack.push((b.clone(), true));
+                    } else {
+                        stack.push((b.clone(), false));
+                        stack.push((a.clone(), false));
+                    }
+                },
+                Proof::Symmetry(SymmetryProof(a)) => stack.push((a.clone(), !rev)),
+                Proof::Reflexivity(_) => {},
+                _ => {
+                    let x = self.minimize(&x);
+                    out.push(if rev { prove_symmetry(x, &self.reg) } else { x });
+                },
+            }
+        }
+
+        out
+    }
+
+    // Returns None, if the chain was not well-formed enough to be shortened.
+    fn shorten_chain(&mut self, links: Vec<ProvenEq>) -> Option<ProvenEq> {
+        let first = links.first()?;
+
+        // points[i] is the term at which links[i] starts, using consistent slot names throughout the chain.
+        let mut points = vec![first.l.clone()];
+        for x in &links {
+            let theta = match_app_id_opt(&x.l, points.last().unwrap())?;
+            points.push(x.r.apply_slotmap_fresh(&theta));
+        }
+
+        // cost[i] is the size of links[..i].
+        let mut cost = vec![0usize];
+        for x in &links {
+            let c = cost.last().unwrap().saturating_add(self.size(x));
+            cost.push(c);
+        }
+
+        let n = links.len();
+        let mut out: Vec<ProvenEq> = Vec::new();
+        let mut i = 0;
+        while i < n {
+            // Find the farthest point that we can reach from points[i] cheaper than using the chain.
+            let mut next = None;
+            for j in (i+2..=n).rev() {
+                if points[i] == points[j] {
+                    next = Some((j, None));
+                    break;
+                }
+                if let Some(x) = self.known_proof(&points[i], &points[j]) {
+                    if self.size(&x) < cost[j] - cost[i] {
+                        next = Some((j, Some(x)));
+                        break;
+                    }
+                }
+            }
+
+            match next {
+                Some((j, x)) => {
+                    out.extend(x);
+                    i = j;
+                },
+                None => {
+                    out.push(links[i].clone());
+                    i += 1;
+                },
+            }
+        }
+
+        let mut out = out.into_iter();
+        let Some(mut acc) = out.next() else {
+            // The whole chain was a detour.
+            return (points[0] == points[n]).then(|| prove_reflexivity(&points[0], &self.reg));
+        };
+        for x in out {
+            acc = prove_transitivity(acc, x, &self.reg);
+        }
+        Some(acc)
+    }
+
+    fn known_proof(&self, l: &AppliedId, r: &AppliedId) -> Option<ProvenEq> {
+        let reg = &self.eg.proof_registry;
+        if let Some(x) = reg.get(&Equation { l: l.clone(), r: r.clone() }) {
+            return Some(x);
+        }
+        let x = reg.get(&Equation { l: r.clone(), r: l.clone() })?;
+        Some(prove_symmetry(x, &self.reg))
+    }
+}
+
+// A non-panicking version of `match_app_id`.
+fn match_app_id_opt(a: &AppliedId, b: &AppliedId) -> Option<SlotMap> {
+    if a.id != b.id || a.m.keys() != b.m.keys() { return None; }
+    Some(a.m.inverse().compose(&b.m))
+}
+
+// Whether both equations are equal up to renaming.
+fn equations_match(a: &Equation, b: &Equation) -> bool {
+    let Some(theta_l) = match_app_id_opt(&a.l, &b.l) else { return false };
+    let Some(theta_r) = match_app_id_opt(&a.r, &b.r) else { return false };
+    theta_l.try_union(&theta_r).is_some_and(|theta| theta.is_bijection())
+}
@@ -28,6 +28,16 @@ mod show;
 #[cfg(feature = "explanations")]
 pub use show::*;
 
+#[cfg(feature = "explanations")]
+mod certificate;
+#[cfg(feature = "explanations")]
+pub use certificate::*;
+
+#[cfg(feature = "explanations")]
+mod minimize;
+#[cfg(feature = "explanations")]
+pub use minimize::*;
+
 #[cfg(not(feature = "explanations"))]
 mod mock;
 #[cfg(not(feature = "explanations"))]
@@ -40,6 +50,9 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         let i1 = self.add_syn_expr(t1);
         let i2 = self.add_syn_expr(t2);
 
+        // Adding the terms might have caused unions, whose congruences still need to be found.
+        self.rebuild();
+
         if !self.eq(&i1, &i2) { panic!("Can't explain an equivalence that does not hold!"); }
 
         let pai1 = self.proven_find_applied_id(&i1);
@@ -172,6 +172,10 @@ pub(crate) fn alpha_normalize<L: Language>(n: &L) -> L {
 
 impl CongruenceProof {
     pub fn check<L: Language, N: Analysis<L>>(&self, eq: &Equation, eg: &EGraph<L, N>) -> ProvenEq {
+        self.check_with_registry(eq, eg, &eg.proof_registry)
+    }
+
+    pub(crate) fn check_with_registry<L: Language, N: Analysis<L>>(&self, eq: &Equation, eg: &EGraph<L, N>, reg: &ProofRegistry) -> ProvenEq {
         let CongruenceProof(child_proofs) = self;
 
         let l = alpha_normalize(&eg.get_syn_node(&eq.l));
@@ -199,7 +203,7 @@ impl CongruenceProof {
 
         let eq = eq.clone();
         let proof = Proof::Congruence(self.clone());
-        eg.proof_registry.insert(Arc::new(ProvenEqRaw { eq, proof }))
+        reg.insert(Arc::new(ProvenEqRaw { eq, proof }))
     }
 }
 
@@ -208,6 +212,10 @@ impl Equation {
         &self.l.slots() | &self.r.slots()
     }
 
+    pub(crate) fn heap_size(&self) -> usize {
+        self.l.m.heap_size() + self.r.m.heap_size()
+    }
+
     #[track_caller]
     pub fn apply_slotmap(&self, m: &SlotMap) -> Self {
         Equation {
@@ -30,7 +30,7 @@ impl ProvenEqRaw {
         out
     }
 
-    fn subproofs(&self) -> Vec<&ProvenEq> {
+    pub(crate) fn subproofs(&self) -> Vec<&ProvenEq> {
         match self.proof() {
             Proof::Explicit(ExplicitProof(j)) => vec![],
             Proof::Reflexivity(ReflexivityProof) => vec![],
@@ -136,6 +136,32 @@ impl<P: Permutation> Group<P> {
             Some(n) => n.ot.len() * n.g.count(),
         }
     }
+
+    // An estimate of the number of bytes allocated by this group on the heap,
+    // where `perm_size` estimates the bytes allocated by a single perm.
+    pub(crate) fn heap_size(&self, perm_size: &impl Fn(&P) -> usize) -> usize {
+        let mut out = perm_size(&self.identity);
+        if let Some(n) = &self.next {
+            out += std::mem::size_of::<Next<P>>();
+            out += n.ot.capacity() * (std::mem::size_of::<(Slot, P)>() + 1);
+            out += n.ot.values().map(perm_size).sum::<usize>();
+            out += n.g.heap_size(perm_size);
+        }
+        out
+    }
+
+    // Visits every perm stored in the stabilizer chain.
+    // Only used to re-attach ProvenPerms to another proof registry.
+    #[cfg(feature = "explanations")]
+    pub(crate) fn for_each_perm_mut(&mut self, f: &mut impl FnMut(&mut P)) {
+        f(&mut self.identity);
+        if let Some(n) = &mut self.next {
+            for p in n.ot.values_mut() {
+                f(p);
+            }
+            n.g.for_each_perm_mut(f);
+        }
+    }
 }
 
 impl<P: Permutation> Next<P> {
@@ -11,14 +11,25 @@ struct State {
     partial_slotmap: SlotMap,
 }
 
+/// Finds all matches of `pattern` in the e-graph.
+///
+/// The e-graph should be clean, see [EGraph::rebuild].
 #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
 pub fn ematch_all<L: Language, N: Analysis<L>>(eg: &EGraph<L, N>, pattern: &Pattern<L>) -> Vec<Subst> {
+    if CHECKS {
+        assert!(eg.is_clean(), "ematch_all called on an e-graph with pending unions!");
+    }
+
+    let constraints = slot_constraints(pattern);
+
     let mut out = Vec::new();
     for i in eg.ids() {
         let i = eg.mk_sem_identity_applied_id(i);
         out.extend(
             ematch_impl(pattern, State::default(), i, eg)
                 .into_iter()
+                // Now that all pattern slots are known, unmapped slots are definitely "other" slots.
+                .filter(|st| constraints.iter().all(|(v, c)| constraint_holds(c, &st.partial_subst[*v], &st.partial_slotmap, true)))
                 .map(final_subst)
         );
     }
@@ -37,6 +48,11 @@ fn ematch_impl<L: Language, N: Analysis<L>>(pattern: &Pattern<L>, st: State, i:
             }
             vec![st]
         },
+        Pattern::ConstrainedPVar(v, c) => {
+            // Prune early: slots that are mapped already can't change anymore.
+            if !constraint_holds(c, &i, &st.partial_slotmap, false) { return Vec::new(); }
+            ematch_impl(&Pattern::PVar(v.clone()), st, i, eg)
+        },
         Pattern::ENode(n, children) => {
             let mut out = Vec::new();
             for nn in eg.enodes_applied(&i) {
@@ -88,6 +104,15 @@ pub(crate) fn nullify_app_ids<L: Language>(l: &L) -> L {
     l
 }
 
+// `i` uses egraph slots, `slotmap` maps them to the pattern slots.
+// If `complete` is false, slots that are not yet mapped might still become any pattern slot, so they are skipped.
+fn constraint_holds(c: &SlotConstraint, i: &AppliedId, slotmap: &SlotMap, complete: bool) -> bool {
+    i.slots().into_iter().all(|s| match slotmap.get(s) {
+        Some(p) => c.allows(Some(p)),
+        None => !complete || c.allows(None),
+    })
+}
+
 fn try_insert_compatible_slotmap_bij(k: Slot, v: Slot, map: &mut SlotMap) -> bool {
     if let Some(v_old) = map.get(k) {
         if v_old != v { return false; }
@@ -1,5 +1,6 @@
 use crate::*;
 use std::any::Any;
+use std::time::{Duration, Instant};
 
 mod ematch;
 pub use ematch::*;
@@ -10,10 +11,46 @@ pub use pattern::*;
 mod subst_method;
 pub use subst_method::*;
 
+mod rule_file;
+pub use rule_file::*;
+
+mod validate;
+pub use validate::*;
+
+#[cfg(feature = "parallel")]
+pub(crate) type SearchResult = Box<dyn Any + Send>;
+#[cfg(not(feature = "parallel"))]
+pub(crate) type SearchResult = Box<dyn Any>;
+
+/// The searcher of a [RewriteT].
+///
+/// With the `parallel` feature, searchers are run concurrently, and hence need to be `Send + Sync`.
+#[cfg(feature = "parallel")]
+pub type Searcher<L, N, T> = Box<dyn Fn(&EGraph<L, N>) -> T + Send + Sync>;
+
+/// The searcher of a [RewriteT].
+///
+/// With the `parallel` feature, searchers are run concurrently, and hence need to be `Send + Sync`.
+#[cfg(not(feature = "parallel"))]
+pub type Searcher<L, N, T> = Box<dyn Fn(&EGraph<L, N>) -> T>;
+
 /// An equational rewrite rule.
 pub struct Rewrite<L: Language, N: Analysis<L> = ()> {
-    pub(crate) searcher: Box<dyn Fn(&EGraph<L, N>) -> Box<dyn Any>>,
-    pub(crate) applier: Box<dyn Fn(Box<dyn Any>, &mut EGraph<L, N>)>,
+    pub(crate) searcher: Searcher<L, N, SearchResult>,
+    pub(crate) applier: Box<dyn Fn(SearchResult, &mut EGraph<L, N>)>,
+
+    // The equation behind this rewrite, if it was built from patterns.
+    pub(crate) rule: Option<Rule<L>>,
+}
+
+/// The equation `lhs = rhs` behind a [Rewrite].
+///
+/// Conditions of conditional rewrites are not part of it.
+#[derive(Clone, Debug)]
+pub struct Rule<L: Language> {
+    pub name: String,
+    pub lhs: Pattern<L>,
+    pub rhs: Pattern<L>,
 }
 
 /// Use this type when you want to build your own [Rewrite].
@@ -22,59 +59,168 @@ pub struct Rewrite<L: Language, N: Analysis<L> = ()> {
 ///
 /// In most cases, `T` is a [Subst].
 pub struct RewriteT<L: Language, N: Analysis<L>, T: Any> {
-    pub searcher: Box<dyn Fn(&EGraph<L, N>) -> T>,
+    pub searcher: Searcher<L, N, T>,
     pub applier: Box<dyn Fn(T, &mut EGraph<L, N>)>,
 }
 
 
-impl<L: Language + 'static, N: Analysis<L> + 'static, T: 'static> RewriteT<L, N, T> {
+impl<L: Language + 'static, N: Analysis<L> + 'static, T: ThreadSafe + 'static> RewriteT<L, N, T> {
     /// Use this function to convert it to an actual [Rewrite].
     pub fn into(self) -> Rewrite<L, N> {
         let searcher = self.searcher;
         let applier = self.applier;
         Rewrite {
             searcher: Box::new(move |eg| Box::new((*searcher)(eg))),
-            applier: Box::new(move |t, eg| (*applier)(any_to_t(t), eg))
+            applier: Box::new(move |t, eg| (*applier)(any_to_t(t), eg)),
+            rule: None,
         }
     }
 }
 
-fn any_to_t<T: Any>(t: Box<dyn Any>) -> T {
+fn any_to_t<T: Any>(t: SearchResult) -> T {
     *t.downcast().unwrap()
 }
 
 /// Applies each given rewrite rule to the E-Graph once.
 /// Returns an indicator for whether the e-graph changed as a result.
+///
+/// With the `parallel` feature, the searchers of all rules run in parallel.
+/// Their results are still applied sequentially, in the order of `rewrites`.
+///
+/// The e-graph is rebuilt once, after all rewrites have been applied.
 #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
 pub fn apply_rewrites<L: Language, N: Analysis<L>>(eg: &mut EGraph<L, N>, rewrites: &[Rewrite<L, N>]) -> bool {
+    let mut rebuild_time = Duration::ZERO;
+    apply_rewrites_until(eg, rewrites, &mut rebuild_time, |_| false)
+}
+
+// Like `apply_rewrites`, but adds the time spent rebuilding to `rebuild_time`.
+// After each rewrite is applied, `stop` is called. If it returns true, the remaining rewrites are skipped.
+pub(crate) fn apply_rewrites_until<L: Language, N: Analysis<L>>(
+    eg: &mut EGraph<L, N>,
+    rewrites: &[Rewrite<L, N>],
+    rebuild_time: &mut Duration,
+    mut stop: impl FnMut(&EGraph<L, N>) -> bool
+) -> bool {
+    // The searchers expect a clean e-graph.
+    timed_rebuild(eg, rebuild_time);
+
     let prog = eg.progress();
 
-    let ts: Vec<Box<dyn Any>> = rewrites.iter().map(|rw| (*rw.searcher)(eg)).collect();
+    let ts = search_all(eg, rewrites);
     for (rw, t) in rewrites.iter().zip(ts.into_iter()) {
         (*rw.applier)(t, eg);
+        if stop(eg) { break; }
     }
 
+    timed_rebuild(eg, rebuild_time);
+
     prog != eg.progress()
 }
 
+fn timed_rebuild<L: Language, N: Analysis<L>>(eg: &mut EGraph<L, N>, rebuild_time: &mut Duration) {
+    let start = Instant::now();
+    eg.rebuild();
+    *rebuild_time += start.elapsed();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn search_all<L: Language, N: Analysis<L>>(eg: &EGraph<L, N>, rewrites: &[Rewrite<L, N>]) -> Vec<SearchResult> {
+    rewrites.iter().map(|rw| (*rw.searcher)(eg)).collect()
+}
+
+#[cfg(feature = "parallel")]
+fn search_all<L: Language, N: Analysis<L>>(eg: &EGraph<L, N>, rewrites: &[Rewrite<L, N>]) -> Vec<SearchResult> {
+    use rayon::prelude::*;
+
+    // The appliers are not thread-safe, so we only hand out the searchers.
+    let searchers: Vec<&Searcher<L, N, SearchResult>> = rewrites.iter().map(|rw| &rw.searcher).collect();
+
+    // `collect` preserves the order of the rules.
+    searchers.par_iter().map(|s| (**s)(eg)).collect()
+}
+
 impl<L: Language + 'static, N: Analysis<L> + 'static> Rewrite<L, N> {
     /// Create a rewrite rule by specifing a left- and right-hand side of your equation.
+    ///
+    /// Panics if the rule is invalid, see [Rewrite::try_new].
     pub fn new(rule: &str, a: &str, b: &str) -> Self {
         Self::new_if(rule, a, b, |_, _| true)
     }
 
     /// Create a conditional rewrite rule.
+    ///
+    /// Panics if the rule is invalid, see [Rewrite::try_new].
     pub fn new_if(rule: &str, a: &str, b: &str, cond: impl Fn(&Subst, &EGraph<L, N>) -> bool + 'static) -> Self {
-        let a = Pattern::parse(a).unwrap();
-        let b = Pattern::parse(b).unwrap();
+        Self::try_new_if(rule, a, b, cond).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create the rewrite rules `a => b` and `b => a`, in this order.
+    ///
+    /// The second rule is named [reverse_rule_name]`(rule)`.
+    ///
+    /// Panics if the rule is invalid in either direction, see [Rewrite::try_new].
+    pub fn new_bidir(rule: &str, a: &str, b: &str) -> Vec<Self> {
+        Self::try_new_bidir(rule, a, b).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Like [Rewrite::new], but without the checks of [Rewrite::try_new].
+    ///
+    /// This is meant for rules that are valid for reasons the checks can't see.
+    /// For example, a language may treat a slot as public, even though it only acts as a binder.
+    ///
+    /// Panics if a pattern doesn't parse.
+    pub fn new_unchecked(rule: &str, a: &str, b: &str) -> Self {
+        let spec = parse_rule(rule, a, b).unwrap_or_else(|e| panic!("{e}"));
+        Self::from_patterns(rule, spec.lhs, spec.rhs, |_, _| true)
+    }
+
+    /// Like [Rewrite::new], but returns an error if
+    /// - a pattern doesn't parse,
+    /// - the rhs uses a pattern-variable or a free slot that doesn't occur on the lhs,
+    /// - a binder on the rhs would capture a free slot of the lhs, or
+    /// - the lhs contains a substitution `b[x := t]`.
+    pub fn try_new(rule: &str, a: &str, b: &str) -> Result<Self, RuleError> {
+        Self::try_new_if(rule, a, b, |_, _| true)
+    }
+
+    /// Like [Rewrite::new_if], but returns an error if the rule is invalid, see [Rewrite::try_new].
+    pub fn try_new_if(rule: &str, a: &str, b: &str, cond: impl Fn(&Subst, &EGraph<L, N>) -> bool + 'static) -> Result<Self, RuleError> {
+        let spec = parse_rule(rule, a, b)?;
+        check_rule(&spec)?;
+        Ok(Self::from_patterns(rule, spec.lhs, spec.rhs, cond))
+    }
+
+    /// Like [Rewrite::new_bidir], but returns an error if the rule is invalid in either direction, see [Rewrite::try_new].
+    pub fn try_new_bidir(rule: &str, a: &str, b: &str) -> Result<Vec<Self>, RuleError> {
+        let fwd = parse_rule(rule, a, b)?;
+        let bwd = Rule { name: reverse_rule_name(rule), lhs: fwd.rhs.clone(), rhs: fwd.lhs.clone() };
+        let rules = [fwd, bwd];
+        for r in &rules {
+            check_rule(r)?;
+        }
+        Ok(rules.into_iter().map(|r| Self::from_patterns(&r.name, r.lhs, r.rhs, |_, _| true)).collect())
+    }
+
+    pub(crate) fn from_patterns(rule: &str, a: Pattern<L>, b: Pattern<L>, cond: impl Fn(&Subst, &EGraph<L, N>) -> bool + 'static) -> Self {
+        let spec = Rule { name: rule.to_string(), lhs: a.clone(), rhs: b.clone() };
         let rule = rule.to_string();
         let a2 = a.clone();
-        RewriteT {
+        let mut rw = RewriteT {
             searcher: Box::new(move |eg| ematch_all(eg, &a)),
             applier: Box::new(move |substs, eg| {
                 Self::apply_substs_cond(substs, &cond, &a2, &b, &rule, eg)
             }),
-        }.into()
+        }.into();
+        rw.rule = Some(spec);
+        rw
+    }
+
+    /// The equation behind this rewrite.
+    ///
+    /// This is `None` for rewrites that were built using [RewriteT].
+    pub fn rule(&self) -> Option<&Rule<L>> {
+        self.rule.as_ref()
     }
 
     #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
@@ -98,7 +244,7 @@ impl<L: Language + 'static, N: Analysis<L> + 'static> Rewrite<L, N> {
 #[derive(PartialEq, Eq)]
 /// A Progress Measure to check saturation of an e-graph with.
 pub struct ProgressMeasure {
-    /// How many classes that were allocated in this e-graph. This measure is strictly growing.
+    /// How many classes that were allocated in this e-graph, including dead ones. This measure is strictly growing, unless the e-graph is pruned or compacted.
     pub number_of_classes: usize,
 
     /// How many classes are still "live". If "number_of_classes" isn't changed, this can only decrease (by union).
@@ -3,5 +3,8 @@ use crate::*;
 mod report;
 pub use report::*;
 
+mod goals;
+pub use goals::*;
+
 mod run;
 pub use run::*;
\ No newline at end of file
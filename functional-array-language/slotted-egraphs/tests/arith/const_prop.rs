@@ -62,3 +62,17 @@ fn const_prop_union() {
 
     assert_eq!(eg.analysis_data(a.id), &ConstProp(Some(42)));
 }
+
+#[test]
+fn const_prop_remove() {
+    let mut eg = EGraph::<Arith, ConstProp>::new();
+    let a = eg.add_expr(RecExpr::parse("a").unwrap());
+    let b = eg.add_expr(RecExpr::parse("42").unwrap());
+    eg.union(&a, &b);
+    let c = eg.add_expr(RecExpr::parse("(add a 1)").unwrap());
+    assert_eq!(eg.analysis_data(c.id), &ConstProp(Some(43)));
+
+    assert!(eg.remove(&Arith::Number(42)));
+    assert_eq!(eg.analysis_data(a.id), &ConstProp(None));
+    assert_eq!(eg.analysis_data(c.id), &ConstProp(None));
+}
@@ -0,0 +1,169 @@
+use crate::*;
+
+#[cfg(feature = "explanations")]
+#[test]
+fn proof_certificate() {
+    let (mut eg, _) = saturate_arith("(mul (var $0) (add (var $1) (var $2)))", 3);
+    let start = parse_arith("(mul (var $0) (add (var $1) (var $2)))");
+    let goal = parse_arith("(add (mul (var $2) (var $0)) (mul (var $0) (var $1)))");
+
+    let rules = arith_rule_defs();
+    let cert = eg.explain_equivalence(start.clone(), goal.clone()).to_certificate(&eg, &rules).unwrap();
+    assert!(!cert.steps.is_empty());
+    cert.check_equation(&start, &goal, &rules).unwrap();
+
+    // the textual form can be checked on its own.
+    let cert2 = ProofCertificate::<Arith>::parse(&cert.to_string()).unwrap();
+    assert_eq!(cert, cert2);
+    cert2.check_equation(&start, &goal, &rules).unwrap();
+
+    // broken certificates are rejected.
+    let mut bad = cert.clone();
+    bad.steps[0].backward = !bad.steps[0].backward;
+    assert!(bad.check(&rules).is_err());
+
+    let mut bad = cert.clone();
+    let other = if bad.steps[0].rule == "mul-comm" { "add-comm" } else { "mul-comm" };
+    bad.steps[0].rule = other.to_string();
+    assert!(bad.check(&rules).is_err());
+
+    assert_eq!(cert.check_equation(&goal, &start, &rules), Err(CertificateError::WrongEquation));
+}
+
+#[cfg(feature = "explanations")]
+#[test]
+fn proof_certificate_with_binders() {
+    let start = parse_arith("(app (lam $0 (add (var $0) (var $1))) (var $2))");
+    let goal = parse_arith("(let $3 (var $2) (add (var $1) (var $3)))");
+
+    let mut eg = EGraph::new();
+    eg.add_expr(start.clone());
+    eg.add_expr(goal.clone());
+    for _ in 0..3 {
+        rewrite_arith(&mut eg);
+    }
+
+    let rules = arith_rule_defs();
+    let cert = eg.explain_equivalence(start.clone(), goal.clone()).to_certificate(&eg, &rules).unwrap();
+    cert.check_equation(&start, &goal, &rules).unwrap();
+    ProofCertificate::<Arith>::parse(&cert.to_string()).unwrap().check_equation(&start, &goal, &rules).unwrap();
+}
+
+#[cfg(feature = "explanations")]
+#[test]
+fn flat_explanation() {
+    let (mut eg, _) = saturate_arith("(mul (var $0) (add (var $1) (var $2)))", 3);
+    let start = parse_arith("(mul (var $0) (add (var $1) (var $2)))");
+    let goal = parse_arith("(add (mul (var $2) (var $0)) (mul (var $0) (var $1)))");
+
+    let prf = eg.explain_equivalence(start.clone(), goal.clone());
+    let flat = prf.to_flat_explanation(&eg);
+    assert_eq!(flat.start, start);
+    assert_eq!(flat.steps.len() + 1, prf.to_flat_string(&eg).lines().count());
+
+    // the steps agree with the ones of the certificate.
+    let rules = arith_rule_defs();
+    let cert = prf.to_certificate(&eg, &rules).unwrap();
+    assert_eq!(flat.steps.len(), cert.steps.len());
+    for (f, c) in flat.steps.iter().zip(&cert.steps) {
+        assert_eq!(f.rule.as_ref(), Some(&c.rule));
+        assert_eq!(f.backward, c.backward);
+        assert_eq!(f.pos, c.pos);
+        assert_eq!(f.dst, c.dst);
+    }
+}
+
+// Pins the text format, which is parsed by the Lean frontend.
+#[cfg(feature = "explanations")]
+#[test]
+fn flat_string() {
+    let mut eg: EGraph<Arith> = EGraph::new();
+    let a = eg.add_expr(parse_arith("(add (var $0) (var $1))"));
+    let b = eg.add_expr(parse_arith("(add (var $1) (var $0))"));
+    eg.union_justified(&a, &b, Some("add-comm".to_string()));
+    let one = eg.add_expr(parse_arith("1"));
+    let two = eg.add_expr(parse_arith("2"));
+    eg.union(&one, &two);
+
+    let start = parse_arith("(lam $2 (mul (add (var $2) (var $3)) 1))");
+    let goal = parse_arith("(lam $2 (mul (add (var $3) (var $2)) 2))");
+    let prf = eg.explain_equivalence(start, goal);
+    let expected = "(lam $2 (mul (add (var $2) (var $3)) 1))\n\
+                    (lam $2 (mul (add (var $2) (var $3)) (Rewrite=> ? 2)))\n\
+                    (lam $2 (mul (Rewrite<= add-comm (add (var $3) (var $2))) 2))";
+    assert_eq!(prf.to_flat_string(&eg), expected);
+}
+
+#[cfg(feature = "explanations")]
+#[test]
+fn minimized_proofs() {
+    let cases = [
+        ("(add (var $0) (var $1))", "(add (var $1) (var $0))"),
+        ("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))", "(mul (add (var $2) (var $1)) (add (var $1) (var $0)))"),
+        ("(mul (var $0) (add (var $1) (var $2)))", "(add (mul (var $2) (var $0)) (mul (var $0) (var $1)))"),
+    ];
+    let rules = arith_rule_defs();
+
+    let mut improved = false;
+    for (start, goal) in cases {
+        let (mut eg, _) = saturate_arith(start, 4);
+        let start = parse_arith(start);
+        let goal = parse_arith(goal);
+
+        let prf = eg.explain_equivalence(start.clone(), goal.clone());
+        let min = eg.minimize_proof(&prf);
+        assert!(min.size() <= prf.size());
+        improved |= min.size() < prf.size();
+
+        let cert = min.to_certificate(&eg, &rules).unwrap();
+        assert_eq!(cert.steps.len(), min.size());
+        cert.check_equation(&start, &goal, &rules).unwrap();
+
+        let min2 = eg.explain_equivalence_minimized(start, goal);
+        assert_eq!(min2.size(), min.size());
+    }
+    assert!(improved);
+}
+
+// Fresh slot names are global, so the e-graph names its slots differently depending on which tests ran before.
+// Flattening has to be correct for any naming.
+#[cfg(feature = "explanations")]
+#[test]
+fn certificates_for_shifted_slot_names() {
+    let cases = [
+        ("(add (var $0) (var $1))", "(add (var $1) (var $0))"),
+        ("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))", "(mul (add (var $2) (var $1)) (add (var $1) (var $0)))"),
+        ("(mul (var $0) (add (var $1) (var $2)))", "(add (mul (var $2) (var $0)) (mul (var $0) (var $1)))"),
+    ];
+    let rules = arith_rule_defs();
+
+    for shift in 0..16 {
+        for (start, goal) in cases {
+            for _ in 0..shift { Slot::fresh(); }
+            let (mut eg, _) = saturate_arith(start, 4);
+            let start = parse_arith(start);
+            let goal = parse_arith(goal);
+
+            let prf = eg.explain_equivalence(start.clone(), goal.clone());
+            prf.to_certificate(&eg, &rules).unwrap().check_equation(&start, &goal, &rules).unwrap();
+            let min = eg.minimize_proof(&prf);
+            min.to_certificate(&eg, &rules).unwrap().check_equation(&start, &goal, &rules).unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "explanations")]
+#[test]
+fn slot_constraints_certificate() {
+    let rules = arith_rule_defs();
+    let cert = |start: &str, b: &str, dst: &str| {
+        let s = format!("start {start}\nstep => @ eta\nsubst ?b {b}\nslot $1 $0\ndst {dst}\n");
+        ProofCertificate::<Arith>::parse(&s).unwrap()
+    };
+
+    cert("(lam $0 (app (var $2) (var $0)))", "(var $2)", "(var $2)").check(&rules).unwrap();
+
+    // eta doesn't apply, as ?b mentions the bound slot.
+    let forged = cert("(lam $0 (app (var $0) (var $0)))", "(var $0)", "(var $0)");
+    assert!(matches!(forged.check(&rules), Err(CertificateError::NoMatch { step: 0 })));
+}
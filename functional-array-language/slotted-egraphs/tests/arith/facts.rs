@@ -0,0 +1,27 @@
+use crate::*;
+
+#[test]
+fn facts_follow_unions() {
+    let mut eg = EGraph::<Arith>::new();
+    let h = eg.add_expr(parse_arith("(add (var $0) (var $1))"));
+    let h2 = eg.add_expr(parse_arith("(add 2 3)"));
+    assert!(eg.add_fact(&h, String::from("h")));
+    assert!(eg.add_fact(&h2, String::from("h2")));
+    assert!(!eg.add_fact(&h, String::from("h'")));
+
+    let comm = eg.add_expr(parse_arith("(add (var $1) (var $0))"));
+    let renamed = eg.add_expr(parse_arith("(add (var $0) (var $2))"));
+    assert_eq!(eg.fact(&comm), None);
+    rewrite_arith(&mut eg);
+
+    // The fact is found using any equal AppliedId, but only if the slots match.
+    assert_eq!(eg.fact(&comm), Some("h"));
+    assert_eq!(eg.fact(&renamed), None);
+
+    // If two facts become equal, the older one is kept.
+    let five = eg.add_expr(parse_arith("5"));
+    assert!(eg.add_fact(&five, String::from("five")));
+    eg.union(&h2, &five);
+    assert_eq!(eg.fact(&five), Some("h2"));
+    assert_eq!(eg.facts().len(), 2);
+}
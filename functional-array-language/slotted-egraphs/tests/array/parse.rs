@@ -36,6 +36,7 @@ fn translate(p: Pattern<Sym>) -> Pattern<Array> {
             }
         }
         Pattern::PVar(x) => Pattern::PVar(x),
+        Pattern::ConstrainedPVar(x, c) => Pattern::ConstrainedPVar(x, c),
         Pattern::Subst(..) => panic!(),
     }
 }
@@ -51,20 +51,16 @@ fn beta() -> Rewrite<Array> {
 }
 
 fn eta() -> Rewrite<Array> {
-    let pat = "(lam $1 (app ?f (var $1)))";
+    let pat = "(lam $1 (app ?f[-$1] (var $1)))";
     let outpat = "?f";
 
-    Rewrite::new_if("eta", pat, outpat, |subst, _| {
-        !subst["f"].slots().contains(&Slot::numeric(1))
-    })
+    Rewrite::new("eta", pat, outpat)
 }
 
 fn my_let_unused() -> Rewrite<Array> {
-    let pat = "(let $1 ?t ?b)";
+    let pat = "(let $1 ?t ?b[-$1])";
     let outpat = "?b";
-    Rewrite::new_if("my-let-unused", pat, outpat, |subst, _| {
-        !subst["b"].slots().contains(&Slot::numeric(1))
-    })
+    Rewrite::new("my-let-unused", pat, outpat)
 }
 
 fn let_var_same() -> Rewrite<Array> {
@@ -26,10 +26,12 @@ pub use std::ops::RangeInclusive;
 
 use memory_stats::memory_stats;
 use std::time::Instant;
+use std::rc::Rc;
+use std::cell::RefCell;
 
 use tracing::*;
 
-fn assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out: W, steps: usize) where W: std::io::Write {
+fn assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out: W, steps: usize) where W: std::io::Write + 'static {
      match binding {
         "slot" => {
             let start = RecExpr::parse(start).unwrap();
@@ -53,68 +55,116 @@ struct Iteration {
     egraph_nodes: usize,
     egraph_classes: usize,
     total_time: f64,
-    found: bool
+    found: bool,
+    egraph_mem: usize,
 }
 
-fn assert_reaches_common<W, L, N>(
-    start: RecExpr<L>, goal: RecExpr<L>, rules: Vec<Rewrite<L, N>>,
-    mut csv_out: W, steps: usize)
-    where W: std::io::Write, L: Language, N: Analysis<L>
-{
-    let mut eg = EGraph::new();
-    let i1 = eg.add_expr(start.clone());
-    for it_number in 0..steps {
-        let start_time = Instant::now();
-
-        let check_span = trace_span!("check").entered();
-        dbg!(it_number, eg.total_number_of_nodes());
+impl Iteration {
+    fn new<L: Language, N: Analysis<L>>(eg: &EGraph<L, N>) -> Self {
         let memory = memory_stats().expect("could not get current memory usage");
-        let out_of_memory = memory.virtual_mem > 4_000_000_000;
-        if out_of_memory {
-            dbg!("reached memory limit!");
-        }
-        let mut it = Iteration {
+        Iteration {
             physical_mem: memory.physical_mem,
             virtual_mem: memory.virtual_mem,
             egraph_nodes: eg.total_number_of_nodes(),
             egraph_classes: eg.ids().len(),
             total_time: 0.0,
-            found: false
-        };
-
-        if let Some(i2) = lookup_rec_expr(&goal, &eg) {
-            if eg.eq(&i1, &i2) {
-                #[cfg(feature = "explanations")]
-                println!("{}", eg.explain_equivalence(start, goal).to_string(&eg));
-                it.found = true;
-            }
-        }
-        let stop = it.found || out_of_memory;
-        check_span.exit();
-
-        if !stop {
-            apply_rewrites(&mut eg, &rules);
+            found: false,
+            egraph_mem: eg.memory_usage().total(),
         }
+    }
 
-        it.total_time = start_time.elapsed().as_secs_f64();
-        writeln!(csv_out, "{}, {}, {}, {}, {}, {}, {}",
+    // The first seven columns are shared with egg-rise.
+    // The last one is the memory held by the e-graph, see `EGraph::memory_usage`.
+    fn write<W: std::io::Write>(&self, csv_out: &mut W, it_number: usize) {
+        writeln!(csv_out, "{}, {}, {}, {}, {}, {}, {}, {}",
             it_number,
-            it.physical_mem,
-            it.virtual_mem,
-            it.egraph_nodes,
-            it.egraph_classes,
-            it.total_time,
-            it.found
+            self.physical_mem,
+            self.virtual_mem,
+            self.egraph_nodes,
+            self.egraph_classes,
+            self.total_time,
+            self.found,
+            self.egraph_mem
         ).unwrap();
+    }
+}
 
-        if stop {
-            return;
-        }
+// Row `n` describes the e-graph after the rewrites were applied `n` times.
+// Its time is spent checking for the goal and applying the rewrites once more, as in egg-rise.
+// Taking the measurements, in particular `EGraph::memory_usage`, is not timed.
+struct Rows<W> {
+    csv_out: W,
+    it_number: usize,
+    current: Iteration,
+    start_time: Instant,
+}
+
+impl<W: std::io::Write> Rows<W> {
+    fn new<L: Language, N: Analysis<L>>(csv_out: W, eg: &EGraph<L, N>) -> Self {
+        let current = Self::measure(eg, 0);
+        Rows { csv_out, it_number: 0, current, start_time: Instant::now() }
     }
 
-    // dbg!(extract::<_, _, AstSizeNoLet>(&i1, &eg));
-    dbg!(&goal);
-    assert!(false);
+    fn measure<L: Language, N: Analysis<L>>(eg: &EGraph<L, N>, it_number: usize) -> Iteration {
+        let _check_span = trace_span!("check").entered();
+        dbg!(it_number, eg.total_number_of_nodes());
+        Iteration::new(eg)
+    }
+
+    // Called whenever the rewrites were applied without reaching the goal.
+    fn next<L: Language, N: Analysis<L>>(&mut self, eg: &EGraph<L, N>) {
+        self.finish(false);
+        self.it_number += 1;
+        self.current = Self::measure(eg, self.it_number);
+        self.start_time = Instant::now();
+    }
+
+    fn finish(&mut self, found: bool) {
+        self.current.total_time = self.start_time.elapsed().as_secs_f64();
+        self.current.found = found;
+        self.current.write(&mut self.csv_out, self.it_number);
+    }
+}
+
+fn assert_reaches_common<W, L, N>(
+    start: RecExpr<L>, goal: RecExpr<L>, rules: Vec<Rewrite<L, N>>,
+    csv_out: W, steps: usize)
+    where W: std::io::Write + 'static, L: Language + 'static, N: Analysis<L>
+{
+    let mut eg = EGraph::new();
+    let i1 = eg.add_expr(start.clone());
+    let goals = Goals::any(vec![Goal::Term(i1.clone(), goal.clone())]);
+
+    let rows = Rc::new(RefCell::new(Rows::new(csv_out, &eg)));
+    let hook_rows = rows.clone();
+    let hook = move |eg: &mut EGraph<L, N>| {
+        let mut rows = hook_rows.borrow_mut();
+        rows.next(eg);
+        if rows.current.virtual_mem > 4_000_000_000 {
+            return Err("reached memory limit!".to_string());
+        }
+        Ok(())
+    };
+
+    // At most `steps` rows, so the rewrites are applied at most `steps - 1` times.
+    let limits = Limits::new(steps - 1, usize::MAX);
+    let report = run_eqsat_goals(&mut eg, &rules, &goals, limits, hook);
+    rows.borrow_mut().finish(goals.is_done(&report.reached_goals));
+
+    match report.stop_reason {
+        StopReason::GoalsReached => {
+            #[cfg(feature = "explanations")]
+            println!("{}", eg.explain_equivalence(start, goal).to_string(&eg));
+        },
+        StopReason::Other(msg) => {
+            dbg!(msg);
+        },
+        _ => {
+            // dbg!(extract::<_, _, AstSizeNoLet>(&i1, &eg));
+            dbg!(&goal);
+            assert!(false);
+        }
+    }
 }
 
 fn to_db(e: RecExpr<Rise>) -> RecExpr<DBRise> {
@@ -156,7 +206,7 @@ fn main() {
 }
 
 #[cfg(feature = "trace")]
-fn may_trace_assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out: W, steps: usize) where W: std::io::Write {
+fn may_trace_assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out: W, steps: usize) where W: std::io::Write + 'static {
 
     use tracing_subscriber;
     // use tracing_subscriber::layer::SubscriberExt;
@@ -196,6 +246,6 @@ fn may_trace_assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out:
 }
 
 #[cfg(not(feature = "trace"))]
-fn may_trace_assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out: W, steps: usize) where W: std::io::Write {
+fn may_trace_assert_reaches<W>(start: &str, goal: &str, binding: &str, csv_out: W, steps: usize) where W: std::io::Write + 'static {
     assert_reaches(start, goal, binding, csv_out, steps);
 }
\ No newline at end of file
@@ -54,12 +54,10 @@ fn beta() -> Rewrite<Rise> {
 }
 
 fn eta() -> Rewrite<Rise> {
-    let pat = "(lam $1 (app ?f (var $1)))";
+    let pat = "(lam $1 (app ?f[-$1] (var $1)))";
     let outpat = "?f";
 
-    Rewrite::new_if("eta", pat, outpat, |subst, _eg| {
-        !subst["f"].slots().contains(&Slot::numeric(1))
-    })
+    Rewrite::new("eta", pat, outpat)
 }
 
 fn eta_expansion() -> Rewrite<Rise> {
@@ -70,11 +68,9 @@ fn eta_expansion() -> Rewrite<Rise> {
 }
 
 fn my_let_unused() -> Rewrite<Rise> {
-    let pat = "(let $1 ?t ?b)";
+    let pat = "(let $1 ?t ?b[-$1])";
     let outpat = "?b";
-    Rewrite::new_if("my-let-unused", pat, outpat, |subst, _eg| {
-        !subst["b"].slots().contains(&Slot::numeric(1))
-    })
+    Rewrite::new("my-let-unused", pat, outpat)
 }
 
 fn let_var_same() -> Rewrite<Rise> {
@@ -280,18 +280,173 @@ slotted_egraph to_slotted_egraph(b_lean_obj_arg e) {
     return (slotted_egraph)(lean_get_external_data(e));
 }
 
+/*
+inductive Result.Error where
+  | ok
+  | init
+  | goal
+  | guide
+  | fact
+  | rewrite
+  | panic
+*/
+
+// IMPORTANT: This needs to match the order of variants of `ErrorCode` in both backends.
+typedef enum backend_error {
+    BACKEND_OK,
+    BACKEND_INIT_ERROR,
+    BACKEND_GOAL_ERROR,
+    BACKEND_GUIDE_ERROR,
+    BACKEND_FACT_ERROR,
+    BACKEND_REWRITE_ERROR,
+    BACKEND_PANIC
+} backend_error;
+
 typedef struct egg_result {
     char* expl;
     egg_egraph graph;
     report rep;
+    backend_error err;
 } egg_result;
 
+typedef struct slotted_term_node {
+    const char* op;
+    const char* slot;
+    size_t arity;
+} slotted_term_node;
+
+typedef struct slotted_term {
+    const slotted_term_node* nodes;
+    size_t len;
+} slotted_term;
+
+typedef struct slotted_expl_step {
+    const char* rule;
+    _Bool backward;
+    const size_t* pos;
+    size_t pos_len;
+    slotted_term dst;
+} slotted_expl_step;
+
+typedef struct slotted_explanation {
+    slotted_term start;
+    const slotted_expl_step* steps;
+    size_t len;
+} slotted_explanation;
+
 typedef struct slotted_result {
-    char* expl;
+    slotted_explanation expl;
+    char* msg;
     slotted_egraph graph;
     report rep;
+    backend_error err;
 } slotted_result;
 
+typedef struct slotted_str_result {
+    char* str;
+    backend_error err;
+} slotted_str_result;
+
+typedef struct slotted_expl_result {
+    slotted_explanation expl;
+    char* msg;
+    backend_error err;
+} slotted_expl_result;
+
+extern void slotted_free_string(char*);
+extern void slotted_free_explanation(slotted_explanation);
+
+/*
+structure Explanation.Slotted.Node where
+  op    : String
+  slot  : String
+  arity : Nat
+
+abbrev Explanation.Slotted.Term := Array Node
+*/
+lean_obj_res slotted_term_to_lean(slotted_term term) {
+    lean_object* nodes = lean_alloc_array(term.len, term.len);
+    for (int idx = 0; idx < term.len; idx++) {
+        slotted_term_node node = term.nodes[idx];
+        lean_object* node_obj = lean_alloc_ctor(0, 3, 0);
+        lean_ctor_set(node_obj, 0, lean_mk_string(node.op));
+        lean_ctor_set(node_obj, 1, lean_mk_string(node.slot));
+        lean_ctor_set(node_obj, 2, lean_usize_to_nat(node.arity));
+        lean_array_cptr(nodes)[idx] = node_obj;
+    }
+    return nodes;
+}
+
+/*
+structure Explanation.Slotted.Step where
+  rule     : String
+  pos      : Array Nat
+  dst      : Term
+  backward : Bool
+
+structure Explanation.Slotted where
+  start : Term
+  steps : Array Step
+*/
+// Does not free `expl`, as it might be part of a larger result.
+lean_obj_res slotted_explanation_to_lean(slotted_explanation expl) {
+    lean_object* steps = lean_alloc_array(expl.len, expl.len);
+    for (int idx = 0; idx < expl.len; idx++) {
+        slotted_expl_step step = expl.steps[idx];
+        lean_object* pos = lean_alloc_array(step.pos_len, step.pos_len);
+        for (int p = 0; p < step.pos_len; p++) {
+            lean_array_cptr(pos)[p] = lean_usize_to_nat(step.pos[p]);
+        }
+        lean_object* step_obj = lean_alloc_ctor(0, 3, sizeof(uint8_t));
+        lean_ctor_set(step_obj, 0, lean_mk_string(step.rule));
+        lean_ctor_set(step_obj, 1, pos);
+        lean_ctor_set(step_obj, 2, slotted_term_to_lean(step.dst));
+        lean_ctor_set_uint8(step_obj, 3 * sizeof(void*), step.backward);
+        lean_array_cptr(steps)[idx] = step_obj;
+    }
+    lean_object* expl_obj = lean_alloc_ctor(0, 2, 0);
+    lean_ctor_set(expl_obj, 0, slotted_term_to_lean(expl.start));
+    lean_ctor_set(expl_obj, 1, steps);
+    return expl_obj;
+}
+
+/*
+inductive Explanation.Raw where
+  | egg     (str : String)
+  | slotted (expl : Explanation.Slotted)
+*/
+lean_obj_res egg_raw_explanation_to_lean(const char* str) {
+    lean_object* raw = lean_alloc_ctor(0, 1, 0);
+    lean_ctor_set(raw, 0, lean_mk_string(str));
+    return raw;
+}
+
+lean_obj_res slotted_raw_explanation_to_lean(slotted_explanation expl) {
+    lean_object* raw = lean_alloc_ctor(1, 1, 0);
+    lean_ctor_set(raw, 0, slotted_explanation_to_lean(expl));
+    return raw;
+}
+
+// Constructs `Except.ok val` if `ok` is true, and `Except.error val` otherwise.
+lean_obj_res mk_except(_Bool ok, lean_object* val) {
+    lean_object* except = lean_alloc_ctor(ok ? 1 : 0, 1, 0);
+    lean_ctor_set(except, 0, val);
+    return except;
+}
+
+// Turns an explanation returned by slotted into an `Except String Explanation.Raw`, and frees it.
+lean_obj_res slotted_expl_result_to_lean(slotted_expl_result res) {
+    lean_object* val;
+    if (res.err == BACKEND_OK) {
+        val = slotted_raw_explanation_to_lean(res.expl);
+    } else {
+        val = lean_mk_string(res.msg);
+    }
+    slotted_free_explanation(res.expl);
+    slotted_free_string(res.msg);
+    return mk_except(res.err == BACKEND_OK, val);
+}
+
 typedef union egraph {
     egg_egraph egg;
     slotted_egraph slotted;
@@ -305,11 +460,14 @@ lean_object* egraph_to_lean(egraph e, _Bool slotted) {
     }
 }
 
+// The `expl` is an `Explanation.Raw` and `msg` is a `String`.
 typedef struct eqsat_result {
     _Bool slotted;
-    char* expl;
+    lean_object* expl;
+    lean_object* msg;
     egraph graph;
     report rep;
+    backend_error err;
 } eqsat_result;
 
 extern egg_result egg_explain_congr(
@@ -356,17 +514,26 @@ eqsat_result run_eqsat_request_core(lean_obj_arg req) {
         slotted_result res = slotted_explain_congr(lhs, rhs, rws, facts, guides, cfg.rust_config, viz_path);
         result = (eqsat_result) {
             .slotted = true,
-            .expl    = res.expl,
+            .expl    = slotted_raw_explanation_to_lean(res.expl),
+            .msg     = lean_mk_string(res.msg),
             .graph   = { .slotted = res.graph },
             .rep     = res.rep,
+            .err     = res.err,
         };
+        // The explanation and message are owned by us, as they have been copied into Lean objects.
+        slotted_free_explanation(res.expl);
+        slotted_free_string(res.msg);
     } else {
         egg_result res = egg_explain_congr(lhs, rhs, rws, facts, guides, cfg.rust_config, viz_path);
+        // Egg returns the error message in place of the explanation.
+        _Bool ok = res.err == BACKEND_OK;
         result = (eqsat_result) {
             .slotted = false,
-            .expl    = res.expl,
+            .expl    = egg_raw_explanation_to_lean(ok ? res.expl : ""),
+            .msg     = lean_mk_string(ok ? "" : res.expl),
             .graph   = { .egg = res.graph },
             .rep     = res.rep,
+            .err     = res.err,
         };
     }
     
@@ -380,30 +547,39 @@ eqsat_result run_eqsat_request_core(lean_obj_arg req) {
 
 /*
 structure Result.Raw where
-  expl    : String
+  expl    : Explanation.Raw
+  msg     : String
   egraph? : Option EGraph
   report? : Option Report
+  err     : Result.Error
 */
 lean_obj_res run_eqsat_request(lean_obj_arg req) {
     eqsat_result result = run_eqsat_request_core(req);
-    lean_object* expl   = lean_mk_string(result.expl);
-    lean_object* graph  = egraph_to_lean(result.graph, result.slotted);
-    lean_object* rep    = report_to_lean(result.rep);
 
-    lean_object* lean_result = lean_alloc_ctor(0, 3, 0);
-    lean_ctor_set(lean_result, 0, expl);
+    lean_object* lean_result = lean_alloc_ctor(0, 4, sizeof(uint8_t));
+    lean_ctor_set(lean_result, 0, result.expl);
+    lean_ctor_set(lean_result, 1, result.msg);
+    lean_ctor_set_uint8(lean_result, 4 * sizeof(void*), (uint8_t)result.err);
 
-    if (graph == NULL) {
+    // Both members of the `egraph` union are pointers, so we can check either one for `NULL`.
+    if (result.graph.slotted == NULL) {
         lean_object* option_nil = lean_alloc_ctor(0, 0, 0); // Option.nil
-        lean_ctor_set(lean_result, 1, option_nil);
         lean_ctor_set(lean_result, 2, option_nil);
+        lean_ctor_set(lean_result, 3, option_nil);
     } else {
+        lean_object* graph = egraph_to_lean(result.graph, result.slotted);
+        lean_object* rep   = report_to_lean(result.rep);
         lean_object* some_graph = lean_alloc_ctor(1, 1, 0); // Option.some
         lean_object* some_report = lean_alloc_ctor(1, 1, 0); // Option.some
         lean_ctor_set(some_graph, 0, graph);
         lean_ctor_set(some_report, 0, rep);
-        lean_ctor_set(lean_result, 1, some_graph);
-        lean_ctor_set(lean_result, 2, some_report);
+        lean_ctor_set(lean_result, 2, some_graph);
+        lean_ctor_set(lean_result, 3, some_report);
+    }
+
+    // The report's string returned by slotted is owned by us, as it has been copied into a Lean string.
+    if (result.slotted) {
+        slotted_free_string(result.rep.rw_stats);
     }
 
     return lean_result;
@@ -415,7 +591,7 @@ extern const char* egg_query_equiv(
     const char* goal
 );
 
-extern const char* slotted_query_equiv(
+extern slotted_expl_result slotted_query_equiv(
     slotted_egraph graph,
     const char* init, 
     const char* goal
@@ -427,9 +603,136 @@ lean_obj_res explain_equiv(b_lean_obj_arg graph, uint8_t slotted, lean_obj_arg i
     
     if (slotted != 0) {
         slotted_egraph graph_c = to_slotted_egraph(graph);
-        return lean_mk_string(slotted_query_equiv(graph_c, init_c, goal_c));
+        return slotted_expl_result_to_lean(slotted_query_equiv(graph_c, init_c, goal_c));
     } else {
         egg_egraph graph_c = to_egg_egraph(graph);
-        return lean_mk_string(egg_query_equiv(graph_c, init_c, goal_c));
+        return mk_except(true, egg_raw_explanation_to_lean(egg_query_equiv(graph_c, init_c, goal_c)));
     }
-}
\ No newline at end of file
+}
+typedef void* slotted_session;
+
+typedef struct slotted_session_result {
+    char* msg;
+    slotted_session session;
+    backend_error err;
+} slotted_session_result;
+
+typedef struct slotted_goal_result {
+    size_t idx;
+    char* msg;
+    backend_error err;
+} slotted_goal_result;
+
+typedef struct slotted_report_result {
+    report rep;
+    char* msg;
+    backend_error err;
+} slotted_report_result;
+
+extern slotted_session_result slotted_session_new(rws_array rws, facts_array facts, config cfg);
+extern slotted_goal_result slotted_session_add_goal(slotted_session session, const char* init, const char* goal);
+extern slotted_str_result slotted_session_add_guide(slotted_session session, const char* guide);
+extern slotted_report_result slotted_session_saturate(slotted_session session);
+extern slotted_expl_result slotted_session_explain_goal(slotted_session session, size_t idx);
+extern slotted_expl_result slotted_session_query_equiv(slotted_session session, const char* init, const char* goal);
+extern void slotted_free_session(slotted_session);
+
+void slotted_session_finalize(slotted_session obj) {
+    slotted_free_session(obj);
+}
+
+void slotted_session_foreach(slotted_session _x, b_lean_obj_arg _y) {
+    // do nothing since `slotted_session` does not contain nested Lean objects
+}
+
+static lean_external_class* slotted_session_class = NULL;
+
+lean_object* slotted_session_to_lean(slotted_session s) {
+    if (slotted_session_class == NULL) {
+        slotted_session_class = lean_register_external_class(slotted_session_finalize, slotted_session_foreach);
+    }
+    return lean_alloc_external(slotted_session_class, s);
+}
+
+slotted_session to_slotted_session(b_lean_obj_arg s) {
+    return (slotted_session)(lean_get_external_data(s));
+}
+
+// Turns an error message returned by slotted into a failed `IO` result, and frees the message.
+lean_obj_res slotted_io_error(char* msg) {
+    lean_object* err = lean_mk_io_user_error(lean_mk_string(msg));
+    slotted_free_string(msg);
+    return lean_io_result_mk_error(err);
+}
+
+
+// opaque Session.new (rws : @& Rewrites.Encoded) (facts : @& Facts.Encoded) (cfg : @& Request.Config) : IO Session
+lean_obj_res session_new(b_lean_obj_arg rws, b_lean_obj_arg facts, b_lean_obj_arg cfg, lean_obj_arg _w) {
+    rws_array rws_c     = rewrites_from_lean_obj(rws);
+    facts_array facts_c = facts_from_lean_obj(facts);
+    lean_config cfg_c   = config_from_lean_obj(cfg);
+
+    slotted_session_result res = slotted_session_new(rws_c, facts_c, cfg_c.rust_config);
+    free_rws_array(rws_c);
+    free(facts_c.ptr);
+
+    if (res.err != BACKEND_OK) {
+        return slotted_io_error(res.msg);
+    }
+    slotted_free_string(res.msg);
+    return lean_io_result_mk_ok(slotted_session_to_lean(res.session));
+}
+
+// opaque Session.addGoal (session : @& Session) (init goal : @& Expression) : IO Nat
+lean_obj_res session_add_goal(b_lean_obj_arg session, b_lean_obj_arg init, b_lean_obj_arg goal, lean_obj_arg _w) {
+    slotted_goal_result res = slotted_session_add_goal(
+        to_slotted_session(session), lean_string_cstr(init), lean_string_cstr(goal)
+    );
+    if (res.err != BACKEND_OK) {
+        return slotted_io_error(res.msg);
+    }
+    slotted_free_string(res.msg);
+    return lean_io_result_mk_ok(lean_usize_to_nat(res.idx));
+}
+
+// opaque Session.addGuide (session : @& Session) (guide : @& Expression) : IO Unit
+lean_obj_res session_add_guide(b_lean_obj_arg session, b_lean_obj_arg guide, lean_obj_arg _w) {
+    slotted_str_result res = slotted_session_add_guide(to_slotted_session(session), lean_string_cstr(guide));
+    if (res.err != BACKEND_OK) {
+        return slotted_io_error(res.str);
+    }
+    slotted_free_string(res.str);
+    return lean_io_result_mk_ok(lean_box(0));
+}
+
+// opaque Session.saturate (session : @& Session) : IO Request.Result.Report
+lean_obj_res session_saturate(b_lean_obj_arg session, lean_obj_arg _w) {
+    slotted_report_result res = slotted_session_saturate(to_slotted_session(session));
+    if (res.err != BACKEND_OK) {
+        return slotted_io_error(res.msg);
+    }
+    lean_object* rep = report_to_lean(res.rep);
+    slotted_free_string(res.rep.rw_stats);
+    slotted_free_string(res.msg);
+    return lean_io_result_mk_ok(rep);
+}
+
+// opaque Session.explainGoal (session : @& Session) (idx : @& Nat) : IO Explanation.Slotted
+lean_obj_res session_explain_goal(b_lean_obj_arg session, b_lean_obj_arg idx, lean_obj_arg _w) {
+    slotted_expl_result res = slotted_session_explain_goal(to_slotted_session(session), lean_usize_of_nat(idx));
+    if (res.err != BACKEND_OK) {
+        slotted_free_explanation(res.expl);
+        return slotted_io_error(res.msg);
+    }
+    lean_object* expl = slotted_explanation_to_lean(res.expl);
+    slotted_free_explanation(res.expl);
+    slotted_free_string(res.msg);
+    return lean_io_result_mk_ok(expl);
+}
+
+// Like `explain_equiv`, but for the e-graph of a session.
+lean_obj_res session_explain_equiv(b_lean_obj_arg session, lean_obj_arg init, lean_obj_arg goal) {
+    return slotted_expl_result_to_lean(slotted_session_query_equiv(
+        to_slotted_session(session), lean_string_cstr(init), lean_string_cstr(goal)
+    ));
+}
@@ -12,7 +12,6 @@ def Normalization.noReduce : Normalization where
   natReduceRws  := false
 
 structure Encoding extends Normalization where
-  -- TODO: Currently, this option implicitly disables `retryWithShapes` as slotted cannot handle shapes, yet.
   slotted     := false
   eraseProofs := true
   -- TODO: Currently, this option implicitly disables defeq rewrites as they can not handle shapes, yet.
@@ -48,6 +47,7 @@ structure Backend where
   iterLimit           := 1000000000000000000
   reporting           := false
   flattenReports      := false
+  sessions            := false
   deriving BEq
 
 inductive Debug.ExitPoint
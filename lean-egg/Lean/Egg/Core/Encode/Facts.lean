@@ -9,6 +9,7 @@ namespace Egg
 structure Fact.Encoded where
   name : String
   expr : Expression
+  deriving BEq
 
 abbrev Facts.Encoded := Array Fact.Encoded
 
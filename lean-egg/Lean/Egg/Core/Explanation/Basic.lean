@@ -5,9 +5,75 @@ open Lean
 
 namespace Egg.Explanation
 
-structure Raw where
-  str     : String
-  slotted : Bool
+namespace Slotted
+
+-- IMPORTANT: The C interface to slotted depends on the order of these fields.
+--
+-- A node of a term returned by the slotted backend. The `slot` is the empty string if the node does
+-- not bind or reference a slot, and `arity` is the number of the node's children.
+structure Node where
+  op    : String
+  slot  : String
+  arity : Nat
+
+-- A term as the array of its nodes in pre-order, that is, each node is followed by its children.
+abbrev Term := Array Node
+
+-- IMPORTANT: The C interface to slotted depends on the order of these fields.
+--
+-- The `rule` is the empty string if the step has no justification, and `pos` is the path of child
+-- indices leading from the root of `dst` to the rewritten subterm.
+structure Step where
+  rule     : String
+  pos      : Array Nat
+  dst      : Term
+  backward : Bool
+
+end Slotted
+
+-- IMPORTANT: The C interface to slotted depends on the order of these fields.
+--
+-- If the explained terms are not equivalent, `start` is empty.
+structure Slotted where
+  start : Slotted.Term
+  steps : Array Slotted.Step
+
+-- IMPORTANT: The C interface to egg depends on the order of these constructors.
+--
+-- The egg backend returns explanations as text, whereas the slotted backend returns them as
+-- structured data. In both cases, an empty explanation indicates that no proof was found.
+inductive Raw where
+  | egg     (str : String)
+  | slotted (expl : Slotted)
+
+def Raw.isEmpty : Raw → Bool
+  | egg str      => str.isEmpty
+  | slotted expl => expl.start.isEmpty
+
+-- The number of steps of the explanation.
+def Raw.length : Raw → Nat
+  | egg str      => str.lineCount
+  | slotted expl => expl.steps.size
+
+partial def Slotted.Term.toString (t : Term) : String :=
+  go 0 |>.fst
+where
+  go (idx : Nat) : String × Nat := Id.run do
+    let some { op, slot, arity } := t[idx]? | return ("", idx)
+    let mut str := if slot.isEmpty then op else s!"{op} ${slot}"
+    let mut next := idx + 1
+    for _ in [:arity] do
+      let (child, n) := go next
+      str := s!"{str} {child}"
+      next := n
+    return (if slot.isEmpty && arity == 0 then str else s!"({str})", next)
+
+instance : ToString Raw where
+  toString
+    | .egg str      => str
+    | .slotted expl => expl.steps.foldl (init := expl.start.toString) fun str step =>
+      let dir := if step.backward then "<=" else "=>"
+      s!"{str}\n{dir} {step.rule} at {step.pos}: {step.dst.toString}"
 
 namespace Rewrite
 
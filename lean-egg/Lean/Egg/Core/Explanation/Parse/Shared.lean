@@ -84,6 +84,7 @@ syntax "≡maxS"                               : fwd_rw_src
 syntax "≡max↔"                               : fwd_rw_src
 syntax "≡imax0"                              : fwd_rw_src
 syntax "≡imaxS"                              : fwd_rw_src
+syntax "≡lvl"                                : fwd_rw_src
 syntax "≡η"                                  : fwd_rw_src
 syntax "≡η+"                                 : fwd_rw_src
 syntax "≡β"                                  : fwd_rw_src
@@ -176,6 +177,7 @@ private def parseFwdRwSrc : (TSyntax `fwd_rw_src) → Source
   | `(fwd_rw_src|≡max↔)  => .level .maxComm
   | `(fwd_rw_src|≡imax0) => .level .imaxZero
   | `(fwd_rw_src|≡imaxS) => .level .imaxSucc
+  | `(fwd_rw_src|≡lvl)   => .level .normalize
   | `(fwd_rw_src|≡η)     => .eta false
   | `(fwd_rw_src|≡η+)    => .eta true
   | `(fwd_rw_src|≡β)     => .beta
@@ -215,6 +217,9 @@ inductive ParseError where
   | missingRw
   | multipleRws
   | nonDefeqProofRw
+  | malformedTerm
+  | invalidRwPos
+  | invalidRwSrc (src : String)
   deriving Inhabited
 
 def ParseError.msgPrefix :=
@@ -228,5 +233,8 @@ instance : Coe ParseError MessageData where
     | missingRw       => s!"{msgPrefix} (non-start) step does not contain a rewrite"
     | multipleRws     => s!"{msgPrefix} step contains multiple rewrites"
     | nonDefeqProofRw => s!"{msgPrefix} step contains non-defeq type-level rewrite in proof"
+    | malformedTerm   => s!"{msgPrefix} malformed term"
+    | invalidRwPos    => s!"{msgPrefix} step rewrites at an invalid position"
+    | invalidRwSrc s  => s!"{msgPrefix} invalid rewrite source '{s}'"
 
 abbrev ParseStepM := ExceptT ParseError <| StateM (Option Rewrite.Info)
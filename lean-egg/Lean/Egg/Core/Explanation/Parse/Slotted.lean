@@ -67,114 +67,119 @@ where
   | subst var to e      => do withSubst var (← go to) do go e
   | unknown             => mkFreshExprMVar none
 
-declare_syntax_cat slot
-declare_syntax_cat slotted_lvl
-declare_syntax_cat slotted_expr
-declare_syntax_cat slotted_expl
-
--- HACK: Dealing with "$" is non-trivial.
-syntax num   : slot
-syntax ident : slot
-
-syntax num                                               : slotted_lvl
-syntax "(" &"uvar" num ")"                               : slotted_lvl
-syntax "(" &"param" str ")"                              : slotted_lvl
-syntax "(" &"succ" slotted_lvl ")"                       : slotted_lvl
-syntax "(" &"max" slotted_lvl slotted_lvl ")"            : slotted_lvl
-syntax "(" &"imax" slotted_lvl slotted_lvl ")"           : slotted_lvl
-syntax "(" &"Rewrite" noWs rw_dir rw_src slotted_lvl ")" : slotted_lvl
-
--- TODO: How should you parse slots?
-syntax "(" &"bvar" slot ")"                               : slotted_expr
-syntax "(" &"fvar" num ")"                                : slotted_expr
-syntax "(" &"mvar" num ")"                                : slotted_expr
-syntax "(" &"sort" slotted_lvl ")"                        : slotted_expr
-syntax "(" &"const" str slotted_lvl* ")"                  : slotted_expr
-syntax "(" &"app" slotted_expr slotted_expr ")"           : slotted_expr
-syntax "(" &"λ" slot slotted_expr slotted_expr ")"        : slotted_expr
-syntax "(" &"∀" slot slotted_expr slotted_expr ")"        : slotted_expr
-syntax "(" &"lit" lit ")"                                 : slotted_expr
-syntax "(" &"proof" slotted_expr ")"                      : slotted_expr
-syntax "(" &"↦" slot slotted_expr slotted_expr ")"        : slotted_expr
-syntax &"_"                                               : slotted_expr
-syntax "(" &"Rewrite" noWs rw_dir rw_src slotted_expr ")" : slotted_expr
-
-syntax slotted_expr+ : slotted_expl
-
-private partial def parseSlot : (TSyntax `slot) → String
-  | `(slot|$n:num)   => s!"{n.getNat}"
-  | `(slot|$i:ident) => s!"{i.getId.toString (escape := false)}"
-  | _                => unreachable!
-
-private partial def parseLevel : (TSyntax `slotted_lvl) → ParseStepM Level
-  | `(slotted_lvl|$n:num)                   => return n.getNat.toLevel
-  | `(slotted_lvl|(uvar $id))               => return .mvar (.fromUniqueIdx id.getNat)
-  | `(slotted_lvl|(param $n))               => return .param (.mkStr1 n.getString)
-  | `(slotted_lvl|(succ $lvl))              => return .succ (← parseLevel lvl)
-  | `(slotted_lvl|(max $lvl₁ $lvl₂))        => return .max (← parseLevel lvl₁) (← parseLevel lvl₂)
-  | `(slotted_lvl|(imax $lvl₁ $lvl₂))       => return .imax (← parseLevel lvl₁) (← parseLevel lvl₂)
-  | `(slotted_lvl|(Rewrite$dir $src $body)) => parseRw dir src body
-  | _                                       => unreachable!
+-- The tree structure of a `Slotted.Term`.
+private inductive Tree where
+  | node (op slot : String) (children : Array Tree)
+  deriving Inhabited
+
+private partial def Term.toTree (t : Term) : Except ParseError Tree := do
+  let (tree, next) ← go 0
+  unless next == t.size do throw .malformedTerm
+  return tree
 where
-  parseRw (dir : TSyntax `rw_dir) (src : TSyntax `rw_src) (body : TSyntax `slotted_lvl) :
-      ParseStepM Level := do
-    unless (← get).isNone do throw .multipleRws
-    let info := parseRwSrc src
-    let dir  := info.dir.merge (parseRwDir dir)
-    set <| some { info with dir, pos? := none : Rewrite.Info }
-    parseLevel body
-
-private abbrev ParseStepResult := Except ParseError <| Expression × (Option Rewrite.Info)
-
-private partial def parseExpr (stx : TSyntax `slotted_expr) : ParseStepResult :=
-  let (e, info?) := go .root stx |>.run none
-  return (← e, info?)
+  go (idx : Nat) : Except ParseError (Tree × Nat) := do
+    let some { op, slot, arity } := t[idx]? | throw .malformedTerm
+    let mut children := #[]
+    let mut next := idx + 1
+    for _ in [:arity] do
+      let (child, n) ← go next
+      children := children.push child
+      next := n
+    return (.node op slot children, next)
+
+private def Tree.nat : Tree → Except ParseError Nat
+  | .node op _ #[] => op.toNat?.getDM (throw .malformedTerm)
+  | _              => throw .malformedTerm
+
+-- Note: Slotted displays string primitives in quotes (cf. `LeanExpr::to_op`).
+private def Tree.str : Tree → Except ParseError String
+  | .node op _ #[] =>
+    if op.length ≥ 2 && op.startsWith "\"" && op.endsWith "\""
+    then return op.drop 1 |>.dropRight 1
+    else throw .malformedTerm
+  | _ => throw .malformedTerm
+
+private def Tree.lit (t : Tree) : Except ParseError Literal :=
+  (Literal.natVal <$> t.nat) <|> (Literal.strVal <$> t.str)
+
+private partial def Tree.level : Tree → Except ParseError Level
+  | .node "uvar" _ #[id]       => return .mvar (.fromUniqueIdx (← id.nat))
+  | .node "param" _ #[n]       => return .param (.mkStr1 (← n.str))
+  | .node "succ" _ #[l]        => return .succ (← l.level)
+  | .node "max" _ #[l₁, l₂]    => return .max (← l₁.level) (← l₂.level)
+  | .node "imax" _ #[l₁, l₂]   => return .imax (← l₁.level) (← l₂.level)
+  | t                          => return (← t.nat).toLevel
+
+private partial def Tree.expr : Tree → Except ParseError Expression
+  | .node "bvar" s #[]         => return .bvar s
+  | .node "fvar" _ #[id]       => return .fvar (.fromUniqueIdx (← id.nat))
+  | .node "mvar" _ #[id]       => return .mvar (.fromUniqueIdx (← id.nat))
+  | .node "sort" _ #[lvl]      => return .sort (← lvl.level)
+  | .node "const" _ cs         => do
+    let some name := cs[0]? | throw .malformedTerm
+    return .const (← name.str).toName (← cs[1:].toArray.mapM Tree.level).toList
+  | .node "app" _ #[fn, arg]   => return .app (← fn.expr) (← arg.expr)
+  | .node "λ" s #[ty, body]    => return .lam s (← ty.expr) (← body.expr)
+  | .node "∀" s #[ty, body]    => return .forall s (← ty.expr) (← body.expr)
+  | .node "lit" _ #[l]         => return .lit (← l.lit)
+  | .node "proof" _ #[p]       => return .proof (← p.expr)
+  | .node "↦" s #[to, e]       => return .subst s (← to.expr) (← e.expr)
+  | .node "◇" _ #[_, e]        => e.expr
+  | .node "_" _ #[]            => return .unknown
+  | _                          => throw .malformedTerm
+
+-- Follows the given path of child indices (cf. `Slotted.Step.pos`) and returns the corresponding
+-- position in the Lean expression, or `none` if the path leads into a universe level. Also returns
+-- whether the path leads into a proof.
+private partial def Tree.rwPos (t : Tree) (path : List Nat) :
+    Except ParseError (Option SubExpr.Pos × Bool) :=
+  go t path .root false
 where
-  go (pos : SubExpr.Pos) : (TSyntax `slotted_expr) → ParseStepM Expression
-    | `(slotted_expr|(bvar $s))                => return .bvar (parseSlot s)
-    | `(slotted_expr|(fvar $id))               => return .fvar (.fromUniqueIdx id.getNat)
-    | `(slotted_expr|(mvar $id))               => return .mvar (.fromUniqueIdx id.getNat)
-    | `(slotted_expr|(sort $lvl))              => return .sort (← parseLevel lvl)
-    | `(slotted_expr|(const $name $lvls*))     => return .const name.getString.toName (← lvls.mapM parseLevel).toList
-    | `(slotted_expr|(app $fn $arg))           => return .app (← go pos.pushAppFn fn) (← go pos.pushAppArg arg)
-    | `(slotted_expr|(λ $var $ty $body))       => return .lam (parseSlot var) (← go pos.pushBindingDomain ty) (← go pos.pushBindingBody body)
-    | `(slotted_expr|(∀ $var $ty $body))       => return .forall (parseSlot var) (← go pos.pushBindingDomain ty) (← go pos.pushBindingBody body)
-    | `(slotted_expr|(lit $l))                 => return .lit (parseLit l)
-    | `(slotted_expr|(proof $p))               => return .proof (← parseProof p pos)
-    | `(slotted_expr|(↦ $var $to $e))          => return .subst (parseSlot var) (← go pos to) (← go pos e)
-    | `(slotted_expr|_)                        => return .unknown
-    | `(slotted_expr|(Rewrite$dir $src $body)) => parseRw dir src body pos
-    | _                                        => unreachable!
-
-  parseProof (p : TSyntax `slotted_expr) (pos : SubExpr.Pos) : ParseStepM Expression := do
-    -- If `p` did not contain a rewrite, all is well and we return `e`. Otherwise, obtain the
-    -- `rwInfo` and make sure it is a defeq rewrite. If not, we have a non-defeq type-level rewrite,
-    -- which we cannot handle, yet.
-    let rwIsOutsideProof := (← get).isSome
-    let e ← go pos p
-    if let some rwInfo ← get then
-      unless rwIsOutsideProof || rwInfo.src.isDefEq do throw .nonDefeqProofRw
-    return e
-
-  parseRw (dir : TSyntax `rw_dir) (src : TSyntax `rw_src) (body : TSyntax `slotted_expr)
-      (pos : SubExpr.Pos) : ParseStepM Expression := do
-    unless (← get).isNone do throw .multipleRws
-    let info := parseRwSrc src
-    let dir  := info.dir.merge (parseRwDir dir)
-    set <| some { info with dir, pos? := pos : Rewrite.Info }
-    go pos body
+  go (t : Tree) (path : List Nat) (pos : SubExpr.Pos) (inProof : Bool) :
+      Except ParseError (Option SubExpr.Pos × Bool) :=
+    match t, path with
+    | _,                          []          => return (some pos, inProof)
+    | .node "app" _ #[fn, _],     0 :: path   => go fn path pos.pushAppFn inProof
+    | .node "app" _ #[_, arg],    1 :: path   => go arg path pos.pushAppArg inProof
+    | .node "λ" _ #[ty, _],       0 :: path
+    | .node "∀" _ #[ty, _],       0 :: path   => go ty path pos.pushBindingDomain inProof
+    | .node "λ" _ #[_, body],     1 :: path
+    | .node "∀" _ #[_, body],     1 :: path   => go body path pos.pushBindingBody inProof
+    | .node "proof" _ #[p],       0 :: path   => go p path pos (inProof := true)
+    | .node "↦" _ #[to, _],       0 :: path   => go to path pos inProof
+    | .node "↦" _ #[_, e],        1 :: path   => go e path pos inProof
+    | .node "◇" _ #[_, e],        1 :: path   => go e path pos inProof
+    | .node "sort" _ #[_],        0 :: _      => return (none, inProof)
+    | .node "const" _ lvls,       i :: _      =>
+      if 0 < i && i < lvls.size then return (none, inProof) else throw .invalidRwPos
+    | _,                          _           => throw .invalidRwPos
+
+private def parseTerm (t : Term) : MetaM Expr := do
+  match t.toTree >>= Tree.expr with
+  | .ok e      => e.toExpr
+  | .error err => throwError err
+
+private def parseRule (rule : String) : MetaM Rewrite.Descriptor := do
+  if rule.isEmpty then throwError ParseError.missingRw
+  match Parser.runParserCategory (← getEnv) `rw_src rule with
+  | .ok stx  => return parseRwSrc ⟨stx⟩
+  | .error _ => throwError (ParseError.invalidRwSrc rule)
+
+private def parseStep (step : Slotted.Step) : MetaM Explanation.Step := do
+  let info ← parseRule step.rule
+  let dir  := info.dir.merge (if step.backward then .backward else .forward)
+  let tree ← match step.dst.toTree with
+    | .ok tree   => pure tree
+    | .error err => throwError err
+  let (pos?, inProof) ← match tree.rwPos step.pos.toList with
+    | .ok res    => pure res
+    | .error err => throwError err
+  -- We cannot handle non-defeq type-level rewrites, yet.
+  if inProof && !info.src.isDefEq then throwError ParseError.nonDefeqProofRw
+  return { info with dir, pos?, dst := ← parseTerm step.dst }
 
 end Slotted
 
-def parseSlottedExpl : (TSyntax `slotted_expl) → MetaM Explanation
-  | `(slotted_expl|$steps:slotted_expr*) => do
-    let some start := steps[0]? | throwError ParseError.noSteps
-    let .ok (start, none) := Slotted.parseExpr start | throwError ParseError.startContainsRw
-    let mut tl : Array Step := #[]
-    for step in steps[1:] do
-      match Slotted.parseExpr step with
-      | .error e             => throwError e
-      | .ok (_, none)        => throwError ParseError.missingRw
-      | .ok (dst, some info) => tl := tl.push { info with dst := ← dst.toExpr }
-    return { start := ← start.toExpr, steps := tl }
-  | _ => unreachable!
+def parseSlottedExpl (expl : Slotted) : MetaM Explanation := do
+  if expl.start.isEmpty then throwError ParseError.noSteps
+  return { start := ← Slotted.parseTerm expl.start, steps := ← expl.steps.mapM Slotted.parseStep }
@@ -132,8 +132,10 @@ where
       )
 
   mkConditionSubproof (fact : Fact) (cond : Expr) : MetaM (Option Expr) := do
-    let rawExpl := egraph.run (← Request.Equiv.encoding fact.type cond ctx)
-    if rawExpl.str.isEmpty then return none
+    let rawExpl ← match egraph.run (← Request.Equiv.encoding fact.type cond ctx) with
+      | .ok raw    => pure raw
+      | .error err => throwError s!"egg backend failed:\n  {err}"
+    if rawExpl.isEmpty then return none
     let expl ← rawExpl.parse
     let proof ← expl.proof rws facts egraph ctx
     let factEqCond ← proof.prove { lhs := fact.type, rhs := cond, rel := .eq }
@@ -25,6 +25,7 @@ protected structure Config where
   shiftCapturedBVars   : Bool
   unionSemantics       : Bool
   allowUnsatConditions : Bool
+  deriving BEq
 
 instance : Coe Config Request.Config where
   coe cfg := {
@@ -101,11 +102,35 @@ structure Result.Report where
   time:        Float
   rwStats:     String
 
+-- IMPORTANT: The C interface to egg depends on the order of these constructors.
+inductive Result.Error where
+  | ok
+  | init
+  | goal
+  | guide
+  | fact
+  | rewrite
+  | panic
+  deriving Inhabited, BEq
+
+def Result.Error.description : Error → String
+  | ok      => "no error"
+  | init    => "invalid lhs"
+  | goal    => "invalid rhs"
+  | guide   => "invalid guide"
+  | fact    => "invalid fact"
+  | rewrite => "invalid rewrite"
+  | panic   => "panic"
+
 -- IMPORTANT: The C interface to egg depends on the order of these fields.
+--
+-- If `err` is not `.ok`, then `msg` contains the error message. Otherwise, `msg` is empty.
 private structure Result.Raw where
-  expl    : String
+  expl    : Explanation.Raw
+  msg     : String
   egraph? : Option EGraph.Obj
   report? : Option Report
+  err     : Result.Error
   deriving Inhabited
 
 @[extern "run_eqsat_request"]
@@ -120,23 +145,30 @@ inductive Failure where
   | backend
   | explLength (len : Nat)
 
+-- Turns the explanation found by the backend into a result. The e-graph is only required if the
+-- explanation is non-empty.
+def Result.ofExpl
+    (expl : Explanation.Raw) (egraph? : Option EGraph) (report : Result.Report)
+    (explLengthLimit : Nat) (onFail : Result.Report → Failure → MetaM MessageData) :
+    MetaM Result := do
+  if expl.isEmpty then
+    throwError ← onFail report .backend
+  else
+    let explLength := expl.length
+    if explLength > explLengthLimit then
+      throwError ← onFail report (.explLength explLength)
+    else
+      let some egraph := egraph? | throwError "egg: internal error: e-graph is absent"
+      return { expl := ← expl.parse, egraph, report }
+
 def run
     (req : Request) (explLengthLimit : Nat) (onFail : Result.Report → Failure → MetaM MessageData) :
     MetaM Result := do
   let raw := runRaw req
-  withTraceNode `egg.explanation (fun _ => return "Explanation") do trace[egg.explanation] raw.expl
-  if "⚡️".isPrefixOf raw.expl then
-    throwError s!"egg backend failed:\n  {raw.expl}"
+  withTraceNode `egg.explanation (fun _ => return "Explanation") do trace[egg.explanation] s!"{raw.expl}"
+  if raw.err != .ok then
+    throwError s!"egg backend failed ({raw.err.description}):\n  {raw.msg}"
   else
     let some report := raw.report? | throwError "egg: internal error: report is absent"
-    if raw.expl.isEmpty then
-      throwError ← onFail report .backend
-    else
-      let explLength := raw.expl.lineCount
-      if explLength > explLengthLimit then
-        throwError ← onFail report (.explLength explLength)
-      else
-        let some obj := raw.egraph? | throwError "egg: internal error: e-graph is absent"
-        let egraph := { obj, slotted := req.cfg.slotted }
-        let expl ← Explanation.Raw.parse { str := raw.expl, slotted := req.cfg.slotted }
-        return { expl, egraph, report }
+    let egraph? := raw.egraph?.map (EGraph.graph · req.cfg.slotted)
+    Result.ofExpl raw.expl egraph? report explLengthLimit onFail
@@ -6,6 +6,13 @@ def EGraph.Obj := EGraph.Pointed.type
 
 instance : Nonempty EGraph.Obj := EGraph.Pointed.property
 
-structure EGraph where
-  obj     : EGraph.Obj
-  slotted : Bool
+private opaque Session.Pointed : NonemptyType.{0}
+
+-- A slotted e-graph which persists across requests (cf. `Egg.Session.get`).
+def Session := Session.Pointed.type
+
+instance : Nonempty Session := Session.Pointed.property
+
+inductive EGraph where
+  | graph (obj : EGraph.Obj) (slotted : Bool)
+  | session (s : Session)
@@ -15,9 +15,17 @@ def Equiv.encoding (init goal : Expr) (ctx : EncodingCtx) : MetaM Equiv :=
 
 end Request
 
+-- On failure, these return the error message of the backend.
+
 @[extern "explain_equiv"]
-private opaque explainEquivRaw (graph : @& EGraph.Obj) (slotted : Bool) (e₁ e₂ : Expression) : String
+private opaque explainEquivRaw (graph : @& EGraph.Obj) (slotted : Bool) (e₁ e₂ : Expression) :
+  Except String Explanation.Raw
+
+@[extern "session_explain_equiv"]
+private opaque explainEquivSession (session : @& Session) (e₁ e₂ : Expression) :
+  Except String Explanation.Raw
 
-def EGraph.run (graph : @& EGraph) (req : Request.Equiv) : Explanation.Raw where
-  str := explainEquivRaw graph.obj graph.slotted req.init req.goal
-  slotted := graph.slotted
+def EGraph.run (graph : @& EGraph) (req : Request.Equiv) : Except String Explanation.Raw :=
+  match graph with
+  | .graph obj slotted => explainEquivRaw obj slotted req.init req.goal
+  | .session session   => explainEquivSession session req.init req.goal
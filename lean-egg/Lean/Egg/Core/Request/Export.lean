@@ -0,0 +1,55 @@
+import Egg.Core.Request.Basic
+open Lean
+
+namespace Egg.Request
+
+-- Cf. `Rust/Parity/src/request.rs`.
+private def escape (s : String) : String :=
+  s.replace "\\" "\\\\" |>.replace "\n" "\\n"
+
+private def exportDirs : Directions → String
+  | .none     => "none"
+  | .forward  => "forward"
+  | .backward => "backward"
+  | .both     => "both"
+
+private def exportEncoding (req : Request) : Array String := Id.run do
+  let mut lines := #[s!"init {escape req.lhs}", s!"goal {escape req.rhs}"]
+  for rw in req.rws do
+    lines := lines.push s!"rewrite {exportDirs rw.dirs} {escape rw.name}"
+    lines := lines.push s!"lhs {escape rw.lhs}"
+    lines := lines.push s!"rhs {escape rw.rhs}"
+    for cond in rw.conds do
+      lines := lines.push s!"cond {escape cond}"
+  for fact in req.facts do
+    lines := lines.push s!"fact {escape fact.name}"
+    lines := lines.push s!"prop {escape fact.expr}"
+  for guide in req.guides do
+    lines := lines.push s!"guide {escape guide}"
+  return lines
+
+-- Renders a request in the format replayed by the parity harness in `Rust/Parity`. As the egg and
+-- slotted backends encode binders differently, this takes the same request encoded for each
+-- backend. The configuration is taken from `egg`.
+def serialize (egg slotted : Request) (src : String) : String :=
+  let cfg := egg.cfg
+  let lines := #[
+    s!"-- {src}",
+    "[config]",
+    s!"optimize_expl {cfg.optimizeExpl}",
+    s!"time_limit {cfg.timeLimit}",
+    s!"node_limit {cfg.nodeLimit}",
+    s!"iter_limit {cfg.iterLimit}",
+    s!"nat_lit {cfg.natLit}",
+    s!"eta {cfg.eta}",
+    s!"eta_expand {cfg.etaExpand}",
+    s!"beta {cfg.beta}",
+    s!"levels {cfg.levels}",
+    s!"shapes {cfg.shapes}",
+    s!"block_invalid_matches {cfg.blockInvalidMatches}",
+    s!"shift_captured_bvars {cfg.shiftCapturedBVars}",
+    s!"union_semantics {cfg.unionSemantics}",
+    s!"allow_unsat_conditions {cfg.allowUnsatConditions}",
+    "[egg]"
+  ] ++ exportEncoding egg ++ #["[slotted]"] ++ exportEncoding slotted
+  "\n".intercalate lines.toList ++ "\n"
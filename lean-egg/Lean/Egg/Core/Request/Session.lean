@@ -0,0 +1,87 @@
+import Egg.Core.Request.Basic
+open Lean
+
+namespace Egg.Session
+
+-- IMPORTANT: Sessions are only supported by the slotted backend.
+
+@[extern "session_new"]
+opaque new (rws : @& Rewrites.Encoded) (facts : @& Facts.Encoded) (cfg : @& Request.Config) : IO Session
+
+-- Returns the index of the new goal, which can be passed to `explainGoal`.
+@[extern "session_add_goal"]
+opaque addGoal (session : @& Session) (init goal : @& Expression) : IO Nat
+
+@[extern "session_add_guide"]
+opaque addGuide (session : @& Session) (guide : @& Guide.Encoded) : IO Unit
+
+-- Runs equality saturation until all goals of the session are proven or a limit is reached.
+@[extern "session_saturate"]
+opaque saturate (session : @& Session) : IO Request.Result.Report
+
+-- Returns the explanation of the given goal, which is empty if it has not been proven (yet).
+@[extern "session_explain_goal"]
+opaque explainGoal (session : @& Session) (idx : @& Nat) : IO Explanation.Slotted
+
+-- Renames the pattern variables `?<idx>` in `exprs` by order of first occurrence, so that rewrites
+-- which only differ in the names of their pattern variables are considered equal.
+private def canonicalize (exprs : Array Expression) : Array Expression := Id.run do
+  let mut vars : Array String := #[]
+  let mut res := #[]
+  for e in exprs do
+    let parts := e.splitOn "?"
+    let mut out := parts.head!
+    for part in parts.tail do
+      let idx := part.takeWhile Char.isDigit
+      if idx.isEmpty then
+        out := out ++ "?" ++ part
+      else
+        let var := vars.findIdx? (· == idx) |>.getD vars.size
+        if var == vars.size then vars := vars.push idx
+        out := out ++ s!"?{var}" ++ part.drop idx.length
+    res := res.push out
+  return res
+
+private structure Key where
+  rws   : Array (String × Directions × Array Expression)
+  facts : Facts.Encoded
+  cfg   : Request.Config
+  deriving BEq
+
+private def Key.of (rws : Rewrites.Encoded) (facts : Facts.Encoded) (cfg : Request.Config) : Key where
+  rws   := rws.map fun rw => (rw.name, rw.dirs, canonicalize (#[rw.lhs, rw.rhs] ++ rw.conds))
+  facts := facts
+  cfg   := cfg
+
+private initialize cache : IO.Ref (Option (Key × Session)) ← IO.mkRef none
+
+-- Returns the session of the previous call if it was created for the same rewrites, facts and
+-- config. Otherwise, the cached session is replaced by a new one.
+def get (rws : Rewrites.Encoded) (facts : Facts.Encoded) (cfg : Request.Config) : IO Session := do
+  let key := Key.of rws facts cfg
+  if let some (cached, session) ← cache.get then
+    if cached == key then return session
+  let session ← new rws facts cfg
+  cache.set <| some (key, session)
+  return session
+
+end Session
+
+namespace Request
+
+-- Like `run`, but adds the goal to the cached session for the request's rewrites, facts and config
+-- (cf. `Session.get`). Thus, terms and equalities from previous requests are kept in the e-graph.
+def runInSession
+    (req : Request) (explLengthLimit : Nat) (onFail : Result.Report → Failure → MetaM MessageData) :
+    MetaM Result := do
+  let (report, expl, session) ←
+    try
+      let session ← Session.get req.rws req.facts req.cfg
+      let idx ← session.addGoal req.lhs req.rhs
+      for guide in req.guides do session.addGuide guide
+      let report ← session.saturate
+      pure (report, Explanation.Raw.slotted (← session.explainGoal idx), session)
+    catch err =>
+      throwError m!"egg backend failed:\n  {err.toMessageData}"
+  withTraceNode `egg.explanation (fun _ => return "Explanation") do trace[egg.explanation] s!"{expl}"
+  Result.ofExpl expl (some <| .session session) report explLengthLimit onFail
@@ -22,6 +22,7 @@ inductive Source.Level where
   | maxComm
   | imaxZero
   | imaxSucc
+  | normalize
   deriving Inhabited, BEq, Hashable
 
 inductive Source.TcSpec where
@@ -84,10 +85,11 @@ def NatLit.description : NatLit → String
   | mod    => "≡%"
 
 def Level.description : Level → String
-  | maxSucc  => "≡maxS"
-  | maxComm  => "≡max↔"
-  | imaxZero => "≡imax0"
-  | imaxSucc => "≡imaxS"
+  | maxSucc   => "≡maxS"
+  | maxComm   => "≡max↔"
+  | imaxZero  => "≡imax0"
+  | imaxSucc  => "≡imaxS"
+  | normalize => "≡lvl"
 
 def TcSpec.description : TcSpec → String
   | dir d    => d.description
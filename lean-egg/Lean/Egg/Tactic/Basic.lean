@@ -1,4 +1,6 @@
 import Egg.Core.Request.Basic
+import Egg.Core.Request.Export
+import Egg.Core.Request.Session
 import Egg.Core.Explanation.Proof
 import Egg.Tactic.Config.Option
 import Egg.Tactic.Config.Modifier
@@ -36,8 +38,7 @@ private def resultToProof
     catch err =>
       -- If proof reconstruction fails but we haven't tried using shapes yet, retry with shapes
       -- (assuming the correcspoding option is enabled).
-      -- TODO: Using slotted e-graphs doesn't support shaped yet.
-      if ctx.cfg.shapes || !retryWithShapes || ctx.cfg.slotted then
+      if ctx.cfg.shapes || !retryWithShapes then
         throw err
       else
         return .retryWithShapes
@@ -59,6 +60,21 @@ where
       unless amb.lvl.contains lmvar do
         throwError m!"egg: final proof contains level mvar {Level.mvar lmvar}"
 
+-- If `egg.exportPath` is set, writes the request to a file in that directory (cf. `Request.serialize`).
+private def exportRequest
+    (goal : Congr) (rws : Rewrites) (facts : Facts) (guides : Guides) (cfg : Config)
+    (amb : MVars.Ambient) : MetaM Unit := do
+  let dir := egg.exportPath.get (← getOptions)
+  if dir.isEmpty then return
+  let egg     ← Request.encoding goal rws facts guides { cfg with slotted := false } amb
+  let slotted ← Request.encoding goal rws facts guides { cfg with slotted := true } amb
+  let mod     := (← getMainModule).toString
+  let fileMap ← getFileMap
+  let line    := (← getRef).getPos?.map (fileMap.toPosition · |>.line) |>.getD 0
+  let file    := s!"{mod}.{line}{if cfg.shapes then ".shapes" else ""}.txt"
+  IO.FS.createDirAll dir
+  IO.FS.writeFile (System.FilePath.mk dir / file) (Request.serialize egg slotted s!"{mod}:{line}")
+
 open Config.Modifier (egg_cfg_mod)
 
 protected partial def eval
@@ -97,8 +113,10 @@ where
       (amb : MVars.Ambient) : TacticM <| Option (Expr × Nat × Request.Result × Bool) := do
     let (req, goalContainsBinder) ← Request.encoding' goal.toCongr rws facts guides cfg amb
     withTraceNode `egg.encoded (fun _ => return "Encoded") do req.trace `egg.encoded
+    exportRequest goal.toCongr rws facts guides cfg amb
     if let .beforeEqSat := cfg.exitPoint then return none
-    let result ← req.run cfg.explLengthLimit (onEqSatFailure cfg goalContainsBinder)
+    let run := if cfg.slotted && cfg.sessions then req.runInSession else req.run
+    let result ← run cfg.explLengthLimit (onEqSatFailure cfg goalContainsBinder)
     result.expl.trace `egg.explanation.steps
     if let .beforeProof := cfg.exitPoint then return none
     let beforeProof ← IO.monoMsNow
@@ -32,6 +32,7 @@ structure Modifier where
   iterLimit           : Option Nat             := none
   reporting           : Option Bool            := none
   flattenReports      : Option Bool            := none
+  sessions            : Option Bool            := none
   retryWithShapes     : Option Bool            := none
   explLengthLimit     : Option Nat             := none
   exitPoint           : Option Debug.ExitPoint := none
@@ -64,6 +65,7 @@ def modify (cfg : Config) (mod : Modifier) : Config where
   iterLimit           := mod.iterLimit.getD cfg.iterLimit
   reporting           := mod.reporting.getD cfg.reporting
   flattenReports      := mod.flattenReports.getD cfg.flattenReports
+  sessions            := mod.sessions.getD cfg.sessions
   retryWithShapes     := mod.retryWithShapes.getD cfg.retryWithShapes
   explLengthLimit     := mod.explLengthLimit.getD cfg.explLengthLimit
   exitPoint           := mod.exitPoint.getD cfg.exitPoint
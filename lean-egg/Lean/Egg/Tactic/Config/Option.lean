@@ -68,5 +68,15 @@ register_egg_options
   iterLimit : Nat
   reporting
   flattenReports
+  sessions "Reuse the e-graph of the previous call to `egg` if it used the same rewrites, facts and
+            configuration. The new goal is added to that e-graph, so that equality saturation can
+            continue where it left off. This is only supported by the slotted backend."
   retryWithShapes "When proof reconstruction fails, try running again with `egg.shapes := true`."
   explLengthLimit : Nat
+
+register_option egg.exportPath : String := {
+  defValue := ""
+  descr := "If non-empty, each request sent to the backend is also written to a file in this
+            directory. These files can be replayed on both backends by the parity harness in
+            `Rust/Parity`."
+}
@@ -0,0 +1,30 @@
+import Egg
+
+-- The `Block Invalid Matches` tests, using *slotted* as the backend. As slots are referred to by
+-- name, invalid matches are detected by checking whether a pattern variable is mapped to an e-class
+-- using any of the slots bound by the rewrite.
+set_option egg.slotted true
+set_option egg.shiftCapturedBVars false
+
+set_option egg.betaReduceRws false
+set_option egg.etaReduceRws false
+set_option egg.beta false
+set_option egg.eta false
+
+theorem thm₁ : ∀ x y : Nat, x = (fun _ => x) y :=
+  fun _ _ => rfl
+
+/-- error: egg failed to prove the goal (saturated) -/
+#guard_msgs in
+set_option egg.blockInvalidMatches true in
+example : (fun x => x) = (fun _ : Nat => (fun x => x) 1) := by
+  egg [thm₁]
+
+theorem thm₂ : ∀ y x : Nat, (fun _ => (fun _ => x) x) y = x :=
+  fun _ _ => rfl
+
+/-- error: egg failed to prove the goal (saturated) -/
+#guard_msgs in
+set_option egg.blockInvalidMatches true in
+example : (fun x => (fun a => (fun a => a) a) 0) = (fun x => x) := by
+  egg [thm₂]
@@ -0,0 +1,49 @@
+import Egg
+
+set_option egg.slotted true
+
+-- The `EraseProofs` tests, using *slotted* as the backend.
+-- Erased proofs are encoded by their type, so proofs of the same proposition are equal.
+
+set_option egg.eraseProofs true
+
+/-- error: egg failed to prove the goal (saturated) -/
+#guard_msgs in
+set_option egg.eraseProofs false in
+set_option egg.beta false in
+set_option egg.eta false in
+example (arr : Array α) (i : Nat) (h₁ h₂ : i < arr.size) : arr[i]'h₁ = arr[i]'h₂ := by
+  egg
+
+example (arr : Array α) (i : Nat) (h₁ h₂ : i < arr.size) : arr[i]'h₁ = arr[i]'h₂ := by
+  egg
+
+set_option egg.eraseProofs false in
+example (i : Nat) (h : i < 10) : (Fin.mk i h).val = i := by
+  have : ∀ n m (g : n < m), (Fin.mk n g).val = n := by simp
+  egg [this]
+
+example (i : Nat) (h : i < 10) : (Fin.mk i h).val = i := by
+  have : ∀ n m (g : n < m), (Fin.mk n g).val = n := by simp
+  egg [this]
+
+example (i : Nat) (h : ∀ i : Nat, i < 10) : (Fin.mk i (h i)).val = i := by
+  have : ∀ n m (g : n < m), (Fin.mk n g).val = n := by simp
+  egg [this]
+
+example (h : ∀ x : Nat, x = Exists.choose (Exists.intro x x.zero_le)) : True = True := by
+  egg [h]
+
+-- β-reduction needs to substitute into the type of the erased proof `h n`. This uses big-step β,
+-- so the small-step `↦proof` rule is covered by the tests in `Rust/Slotted/src/beta.rs` instead.
+example (f : (n : Nat) → n < 10 → Nat) (h : ∀ n, n < 10) : (fun n => f n (h n)) 3 = f 3 (h 3) := by
+  egg
+
+/-- error: egg failed to prove the goal (saturated) -/
+#guard_msgs in
+set_option egg.eraseProofs false in
+example
+    (f : (a b : Nat) → a > b → Nat) (g : Nat → Nat) (a₁ a₂ b₁ b₂ c d : Nat) (h₁ : a₁ > b₁)
+    (h₂ : a₂ > b₂) (h₃ : a₁ = c) (h₄ : a₂ = c) (h₅ : b₁ = d) (h₆ : d = b₂) :
+    g (g (f a₁ b₁ h₁)) = g (g (f a₂ b₂ h₂)) := by
+  egg [*]
@@ -0,0 +1,27 @@
+import Egg
+
+set_option egg.slotted true
+
+-- Tests for universe level normalization, using *slotted* as the backend. Each example requires
+-- an equality between universe levels, which only holds up to normalization.
+
+-- Associativity of `Level.max`.
+example (h : ∀ γ : Sort (max u (max v w)), γ = id γ) (α : Sort (max (max u v) w)) : α = id α := by
+  egg [h]
+
+-- Idempotence of `Level.max`.
+example (h : ∀ γ : Sort u, γ = id γ) (α : Sort (max u u)) : α = id α := by
+  egg [h]
+
+-- `Level.imax` with a right side which is not zero.
+example (h : ∀ γ : Sort (max u (v + 1)), γ = id γ) (α : Sort (imax u (v + 1))) : α = id α := by
+  egg [h]
+
+example (h : ∀ γ : Type (max u v), γ = id γ) (α : Type u) (β : Type v) : (α × β) = id (α × β) := by
+  egg [h]
+
+/-- error: egg failed to prove the goal (saturated) -/
+#guard_msgs in
+set_option egg.levels false in
+example (h : ∀ γ : Sort (max u (max v w)), γ = id γ) (α : Sort (max (max u v) w)) : α = id α := by
+  egg [h]
@@ -0,0 +1,18 @@
+import Egg
+
+set_option egg.slotted true
+set_option egg.sessions true
+
+-- Tests for reusing the e-graph across calls to `egg`, using *slotted* as the backend. Consecutive
+-- calls with the same rewrites add their goal to the e-graph of the previous call.
+
+example (a b c : Nat) : a + b = b + a ∧ b + c = c + b ∧ a + b = b + a := by
+  refine ⟨?_, ?_, ?_⟩
+  all_goals egg [Nat.add_comm]
+
+-- Changing the rewrites starts a new session.
+example (a b c : Nat) : a + (b + c) = (a + b) + c ∧ a + b = b + a := by
+  constructor
+  · egg [Nat.add_assoc]
+  · egg [Nat.add_comm]
+
@@ -0,0 +1,59 @@
+import Egg
+
+set_option egg.slotted true
+set_option egg.shapes true
+
+section Basic
+
+example (a : Nat) : a = a := by
+  egg
+
+example (a b : Nat) (h : a = b) : a = b := by
+  egg [h]
+
+example (a b c : Nat) (h₁ : a = b) (h₂ : b = c) : a = c := by
+  egg [h₁, h₂]
+
+example (a b : Nat) : a + b = b + a := by
+  egg [Nat.add_comm]
+
+example (a b c : Nat) : (a + b) + c = (c + b) + a := by
+  egg [Nat.add_comm, Nat.add_assoc]
+
+example (a : Nat) (h : ∀ x : Nat, x + 1 = 1 + x) : a + 1 = 1 + a := by
+  egg [h]
+
+def f : Nat → Nat
+  | .zero   => .zero
+  | .succ n => f n
+
+def g : Nat → Nat
+  | .zero   => .zero
+  | .succ n => g n
+
+example : f (g Nat.zero.succ.succ) = .zero := by
+  egg [f, g]
+
+end Basic
+
+section Binders
+
+example : (fun x : Nat => 0) = (fun x => 0 + 0) := by
+  egg [Nat.add_zero]
+
+example : (fun x => x) = (fun x => x + 0) := by
+  egg [Nat.add_zero]
+
+example : (fun x => x) = (fun x => 0 + 0 + x) := by
+  egg [Nat.zero_add]
+
+example : (fun x => x) = (fun x => 0 + x) := by
+  egg [Nat.zero_add]
+
+example (f : (Nat → Nat) → Bool) : f (fun x => x) = f (fun x => x + 0) := by
+  egg [Nat.add_zero]
+
+example (h : ∀ x y : Nat, x = y ↔ y = x) : (∀ x y : Nat, x = y) ↔ (∀ a b : Nat, b = a + 0) := by
+  egg [h, Nat.add_zero]
+
+end Binders
@@ -11,24 +11,7 @@ use crate::nat_lit::*;
 use crate::rewrite::*;
 use crate::shift::*;
 use crate::subst::*;
-
-#[repr(C)]
-pub struct Config {
-    optimize_expl:          bool,
-    time_limit:             usize,
-    node_limit:             usize,
-    iter_limit:             usize, 
-    nat_lit:                bool, 
-    eta:                    bool,
-    eta_expand:             bool,
-    beta:                   bool,
-    levels:                 bool,
-    shapes:                 bool,
-    block_invalid_matches:  bool,
-    shift_captured_bvars:   bool,
-    union_semantics:        bool,
-    allow_unsat_conditions: bool
-}
+pub use lean_egg_request::Config;
 
 pub struct ExplainedCongr {
     pub expl:     String,
@@ -5,10 +5,10 @@ use core::ffi::CStr;
 use std::ffi::CString;
 use std::ptr::null;
 use libc::c_double;
-use std::str::FromStr;
 use basic::*;
 use result::*;
 use rewrite::*;
+pub use lean_egg_request::RewriteDirections;
 
 mod analysis;
 mod basic;
@@ -18,6 +18,7 @@ mod eta;
 mod lean_expr;
 mod levels;
 mod nat_lit;
+pub mod replay;
 mod result;
 mod rewrite;
 mod shift;
@@ -51,15 +52,6 @@ impl CStringArray {
     }
 }
 
-#[repr(C)]
-#[derive(PartialEq)]
-pub enum RewriteDirections {
-    None,
-    Forward,
-    Backward,
-    Both
-}
-
 #[repr(C)]
 pub struct CRewrite {
     name:  *const c_char,
@@ -89,21 +81,7 @@ impl CRewritesArray {
             let lhs_str       = lhs_c_str.to_str().unwrap();
             let rhs_str       = rhs_c_str.to_str().unwrap();
             let conds_strs    = rw.conds.to_vec();
-            let lhs           = Pattern::from_str(lhs_str).expect("Failed to parse lhs");
-            let rhs           = Pattern::from_str(rhs_str).expect("Failed to parse rhs");
-            let conds: Vec<_> = conds_strs.iter().map(|cond| Pattern::from_str(cond).expect("Failed to parse condition")).collect();
-
-            if rw.dirs == RewriteDirections::Forward || rw.dirs == RewriteDirections::Both {
-                res.push(RewriteTemplate { name: name_str.to_string(), lhs: lhs.clone(), rhs: rhs.clone(), conds: conds.clone() })
-            }
-            if rw.dirs == RewriteDirections::Backward || rw.dirs == RewriteDirections::Both {
-                // It is important that we use the "-rev" suffix for reverse rules here, as this is also
-                // what's used for adding the reverse rule when using egg's `rewrite!(_; _ <=> _)` macro.
-                // If we choose another naming scheme, egg may complain about duplicate rules when 
-                // `rw.dir == RewriteDirection::Both`. This is the case, for example, for the rewrite
-                // `?a + ?b = ?b + ?a`.
-                res.push(RewriteTemplate { name: format!("{name_str}-rev"), lhs: rhs, rhs: lhs, conds })
-            }
+            res.extend(parse_templates(name_str, lhs_str, rhs_str, &conds_strs, &rw.dirs)?);
         }
         Ok(res)
     }
@@ -190,11 +168,13 @@ impl CReport {
     }
 }
 
+// If `err` is not `ErrorCode::None`, then `expl` contains the error message.
 #[repr(C)]
 pub struct EqsatResult {
     expl: *const c_char,
     graph: Option<Box<LeanEGraph>>,
-    report: CReport
+    report: CReport,
+    err: ErrorCode
 }
 
 #[no_mangle]
@@ -214,7 +194,7 @@ pub extern "C" fn egg_explain_congr(
 
     let rw_templates = rws.to_templates();
     if let Err(rws_err) = rw_templates { 
-        return EqsatResult { expl: string_to_c_str(rws_err.to_string()), graph: None, report: CReport::none() }
+        return EqsatResult { expl: string_to_c_str(rws_err.to_string()), graph: None, report: CReport::none(), err: rws_err.code() }
     }
     let rw_templates = rw_templates.unwrap();
 
@@ -223,14 +203,15 @@ pub extern "C" fn egg_explain_congr(
 
     let res = explain_congr(init, goal, rw_templates, facts, guides, cfg, viz_path);
     if let Err(res_err) = res {
-        return EqsatResult { expl: string_to_c_str(res_err.to_string()), graph: None, report: CReport::none() }
+        return EqsatResult { expl: string_to_c_str(res_err.to_string()), graph: None, report: CReport::none(), err: res_err.code() }
     }
     let ExplainedCongr { expl, egraph, report, rw_stats } = res.unwrap();
 
     return EqsatResult {
         expl: string_to_c_str(expl),
         graph: Some(Box::new(egraph)),
-        report: CReport::from_report(report, rw_stats) 
+        report: CReport::from_report(report, rw_stats),
+        err: ErrorCode::None
     }
 }
 
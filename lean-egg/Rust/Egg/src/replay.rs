@@ -0,0 +1,53 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use egg::*;
+use lean_egg_request::*;
+use crate::basic::explain_congr;
+use crate::rewrite::*;
+use crate::result::*;
+
+// Runs a request recorded by the `egg.exportPath` option without Lean. This is used by the parity
+// harness in `Rust/Parity` to compare the egg and slotted backends.
+//
+// Note: egg panics on some malformed inputs, so we catch panics here.
+pub fn replay(enc: &Encoding, cfg: Config) -> Result<Outcome, String> {
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let mut rw_templates = vec![];
+        for rw in &enc.rws {
+            rw_templates.extend(parse_templates(&rw.name, &rw.lhs, &rw.rhs, &rw.conds, &rw.dirs)?);
+        }
+        explain_congr(enc.init.clone(), enc.goal.clone(), rw_templates, enc.facts.clone(), enc.guides.clone(), cfg, None)
+    }));
+    let congr = match res {
+        Ok(res)      => res.map_err(|err: Error| err.to_string())?,
+        Err(payload) => {
+            let msg = 
+                if let Some(s) = payload.downcast_ref::<&str>() { s.to_string() } 
+                else if let Some(s) = payload.downcast_ref::<String>() { s.clone() } 
+                else { "unknown panic".to_string() };
+            return Err(format!("Panicked: {msg}"))
+        }
+    };
+
+    // egg's runner also records the iteration which is stopped by the hook before applying any
+    // rewrites, so that iteration is not counted (cf. `Outcome::iterations`).
+    let proved_by_hook = matches!(&congr.report.stop_reason, StopReason::Other(msg) if msg == "search complete");
+    let iterations = congr.report.iterations - proved_by_hook as usize;
+
+    let stop_reason = match congr.report.stop_reason {
+        StopReason::Saturated         => "saturated".to_string(),
+        StopReason::IterationLimit(_) => "reached iteration limit".to_string(),
+        StopReason::NodeLimit(_)      => "reached node limit".to_string(),
+        StopReason::TimeLimit(_)      => "reached time limit".to_string(),
+        // Cf. the hook in `explain_congr`.
+        StopReason::Other(msg) if msg == "search complete" => "proved goal".to_string(),
+        StopReason::Other(msg)        => msg,
+    };
+    Ok(Outcome {
+        expl:       congr.expl,
+        iterations,
+        stop_reason,
+        nodes:      congr.report.egraph_nodes,
+        classes:    congr.report.egraph_classes,
+        time:       congr.report.total_time
+    })
+}
@@ -7,15 +7,42 @@ pub enum Error {
     Rewrite(String),
 }
 
+// The kinds of errors reported across the FFI. `None` indicates that no error occurred.
+// IMPORTANT: The C interface depends on the order of these variants, which is shared with the 
+//            slotted backend.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    None,
+    Init,
+    Goal,
+    Guide,
+    Fact,
+    Rewrite,
+}
+
+impl Error {
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Init(_)    => ErrorCode::Init,
+            Error::Goal(_)    => ErrorCode::Goal,
+            Error::Guide(_)   => ErrorCode::Guide,
+            Error::Fact(_)    => ErrorCode::Fact,
+            Error::Rewrite(_) => ErrorCode::Rewrite,
+        }
+    }
+}
+
 impl ToString for Error {
 
     fn to_string(&self) -> String {
         match self {
-            Error::Init(s)    => format!("⚡️ {s}"),
-            Error::Goal(s)    => format!("⚡️ {s}"),
-            Error::Guide(s)   => format!("⚡️ {s}"),
-            Error::Fact(s)    => format!("⚡️ {s}"),
-            Error::Rewrite(s) => format!("⚡️ {s}"),
+            Error::Init(s)    => s.clone(),
+            Error::Goal(s)    => s.clone(),
+            Error::Guide(s)   => s.clone(),
+            Error::Fact(s)    => s.clone(),
+            Error::Rewrite(s) => s.clone(),
         }
     }
 }
@@ -5,6 +5,8 @@ use crate::lean_expr::*;
 use crate::analysis::*;
 use crate::bvar_correction::*;
 use crate::valid_match::*;
+use crate::RewriteDirections;
+use std::str::FromStr;
 
 pub struct RewriteTemplate {
     pub name:  String,
@@ -13,6 +15,30 @@ pub struct RewriteTemplate {
     pub conds: Vec<Pattern<LeanExpr>>
 }
 
+// Parses a rewrite into one template per direction in `dirs`.
+pub fn parse_templates(name: &str, lhs: &str, rhs: &str, conds: &[String], dirs: &RewriteDirections) -> Res<Vec<RewriteTemplate>> {
+    let parse = |what: &str, s: &str| Pattern::from_str(s).map_err(|err| {
+        Error::Rewrite(format!("Failed to parse {what} of rewrite '{name}': {err}\n\n  {s}"))
+    });
+    let lhs   = parse("lhs", lhs)?;
+    let rhs   = parse("rhs", rhs)?;
+    let conds = conds.iter().map(|cond| parse("condition", cond)).collect::<Res<Vec<_>>>()?;
+
+    let mut res: Vec<RewriteTemplate> = vec![];
+    if *dirs == RewriteDirections::Forward || *dirs == RewriteDirections::Both {
+        res.push(RewriteTemplate { name: name.to_string(), lhs: lhs.clone(), rhs: rhs.clone(), conds: conds.clone() })
+    }
+    if *dirs == RewriteDirections::Backward || *dirs == RewriteDirections::Both {
+        // It is important that we use the "-rev" suffix for reverse rules here, as this is also
+        // what's used for adding the reverse rule when using egg's `rewrite!(_; _ <=> _)` macro.
+        // If we choose another naming scheme, egg may complain about duplicate rules when 
+        // `rw.dir == RewriteDirection::Both`. This is the case, for example, for the rewrite
+        // `?a + ?b = ?b + ?a`.
+        res.push(RewriteTemplate { name: format!("{name}-rev"), lhs: rhs, rhs: lhs, conds })
+    }
+    Ok(res)
+}
+
 pub fn templates_to_rewrites(
     templates: Vec<RewriteTemplate>, 
     facts: HashMap<Id, String>, 
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+
+# Regenerates the request corpus in `requests` by elaborating the Lean test suite with the
+# `egg.exportPath` option set. Each `egg` call of a test writes one request file (cf. `exportRequest`
+# in `Egg.Tactic.Basic`). Pass test files as arguments to only export their requests.
+
+parity_dir="$(realpath -s "$(dirname "$0")")"
+requests_dir="$parity_dir/requests"
+tests=()
+for test in "$@"; do tests+=("$(realpath -s "$test")"); done
+cd "$parity_dir/../.."
+
+if [ "${#tests[@]}" -eq 0 ]; then
+  rm -f "$requests_dir"/*.txt
+  mapfile -d '' tests < <(find Lean/Egg/Tests -type f -name '*.lean' -not -path '*/mathlib4/*' -print0 | sort -z)
+fi
+
+lake build Egg || exit 1
+for test in "${tests[@]}"; do
+  lake env lean -R Lean -Dweak.egg.exportPath="$requests_dir" "$test" > /dev/null || echo "failed to elaborate $test"
+done
@@ -0,0 +1,145 @@
+// Replays requests recorded by the `egg.exportPath` option on both the egg and the slotted backend,
+// and reports where they differ in provability, iteration counts and explanation lengths.
+//
+// Usage: cargo run --release -- <request file or directory>...
+//
+// Directories are searched for `.txt` files (non-recursively). The exit code is non-zero if the
+// backends disagree on the provability of any request, or if a request fails on either backend.
+//
+// The corpus in `requests` is exported from the Lean test suite by `gen_corpus.sh`.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use lean_egg_request::*;
+use request::*;
+
+mod request;
+
+// The parts of a backend's result which are compared.
+struct Summary {
+    proved:      bool,
+    iterations:  usize,
+    expl_length: usize,
+    stop_reason: String
+}
+
+type Backend = fn(&Encoding, Config) -> Result<Outcome, String>;
+
+fn run(backend: Backend, enc: &Encoding, cfg: Config) -> Result<Summary, String> {
+    let out = backend(enc, cfg)?;
+    Ok(Summary {
+        proved:      !out.expl.is_empty(),
+        iterations:  out.iterations,
+        // Cf. `Egg.Request.run` on the Lean side, which measures explanations by their line count.
+        expl_length: out.expl.lines().count(),
+        stop_reason: out.stop_reason
+    })
+}
+
+fn collect_files(args: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = vec![];
+    for arg in args {
+        let path = Path::new(arg);
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path).map_err(|err| format!("{arg}: {err}"))?;
+            let mut dir_files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+                .collect();
+            dir_files.sort();
+            files.append(&mut dir_files);
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+enum Verdict {
+    Agree,
+    DiffersInProvability,
+    DiffersOtherwise,
+    Failed
+}
+
+fn check(path: &Path) -> Verdict {
+    println!("{}", path.display());
+    let req = match std::fs::read_to_string(path).map_err(|err| err.to_string()).and_then(|text| parse(&text)) {
+        Ok(req)  => req,
+        Err(err) => { println!("  invalid request: {err}"); return Verdict::Failed }
+    };
+    let (egg, slotted) = match (run(egg_for_lean::replay::replay, &req.egg, req.cfg), run(slotted_for_lean::replay::replay, &req.slotted, req.cfg)) {
+        (Ok(egg), Ok(slotted)) => (egg, slotted),
+        (egg, slotted) => {
+            if let Err(err) = egg     { println!("  egg failed: {err}") }
+            if let Err(err) = slotted { println!("  slotted failed: {err}") }
+            return Verdict::Failed
+        }
+    };
+
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    println!("  provable:    egg {:<6} slotted {}", yes_no(egg.proved), yes_no(slotted.proved));
+    println!("  iterations:  egg {:<6} slotted {}", egg.iterations, slotted.iterations);
+    println!("  expl length: egg {:<6} slotted {}", egg.expl_length, slotted.expl_length);
+    println!("  stopped:     egg ({}), slotted ({})", egg.stop_reason, slotted.stop_reason);
+
+    if egg.proved != slotted.proved {
+        Verdict::DiffersInProvability
+    } else if egg.iterations != slotted.iterations || egg.expl_length != slotted.expl_length {
+        Verdict::DiffersOtherwise
+    } else {
+        Verdict::Agree
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        eprintln!("usage: lean-egg-parity <request file or directory>...");
+        return ExitCode::FAILURE
+    }
+    let files = match collect_files(&args) {
+        Ok(files) => files,
+        Err(err)  => { eprintln!("{err}"); return ExitCode::FAILURE }
+    };
+
+    let (mut agree, mut provability, mut otherwise, mut failed) = (0, 0, 0, 0);
+    for file in &files {
+        match check(file) {
+            Verdict::Agree                => agree += 1,
+            Verdict::DiffersInProvability => provability += 1,
+            Verdict::DiffersOtherwise     => otherwise += 1,
+            Verdict::Failed               => failed += 1
+        }
+    }
+
+    println!();
+    println!("{} requests: {agree} agree, {provability} differ in provability, {otherwise} differ otherwise, {failed} failed", files.len());
+    if provability + failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("requests").join(name)
+    }
+
+    // The goal of Shapes.33 is proven in the first round of rewrites, which both backends count.
+    #[test]
+    fn backends_agree_on_proven_goal() {
+        let req = parse(&std::fs::read_to_string(corpus("Egg.Tests.Shapes.33.shapes.txt")).unwrap()).unwrap();
+        let egg = run(egg_for_lean::replay::replay, &req.egg, req.cfg).unwrap();
+        let slotted = run(slotted_for_lean::replay::replay, &req.slotted, req.cfg).unwrap();
+        assert!(egg.proved && slotted.proved);
+        assert_eq!((egg.iterations, slotted.iterations), (1, 1));
+        assert_eq!(egg.expl_length, slotted.expl_length);
+    }
+
+    // The goal of Shapes.16 holds before any rewrite is applied.
+    #[test]
+    fn backends_agree_without_rewriting() {
+        assert!(matches!(check(&corpus("Egg.Tests.Shapes.16.shapes.txt")), Verdict::Agree));
+    }
+}
@@ -0,0 +1,240 @@
+// A request as recorded by the `egg.exportPath` option. The egg and slotted backends use different
+// encodings of binders, so a request contains one encoding per backend:
+//
+//   -- Comments start with `--`.
+//   [config]
+//   time_limit 30
+//   beta true
+//   [egg]
+//   init (app (const "f") (fvar 1))
+//   goal (app (const "f") (fvar 2))
+//   rewrite both h
+//   lhs (fvar 1)
+//   rhs (fvar 2)
+//   cond ?1
+//   fact h₁
+//   prop (fvar 3)
+//   guide (fvar 4)
+//   [slotted]
+//   ...
+//
+// Each line consists of a keyword followed by its argument. A `lhs`, `rhs` or `cond` line belongs
+// to the preceding `rewrite`, and a `prop` line belongs to the preceding `fact`. In arguments, line
+// breaks are escaped as `\n` and backslashes as `\\`. Config options which are omitted take the
+// default value of the corresponding Lean option.
+
+use lean_egg_request::*;
+
+pub struct Request {
+    pub cfg:     Config,
+    pub egg:     Encoding,
+    pub slotted: Encoding
+}
+
+enum Section {
+    None,
+    Config,
+    Egg,
+    Slotted
+}
+
+fn unescape(s: &str) -> String {
+    let mut res = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n'))  => { res.push('\n'); chars.next(); },
+            ('\\', Some('\\')) => { res.push('\\'); chars.next(); },
+            _                  => res.push(c)
+        }
+    }
+    res
+}
+
+fn parse_bool(s: &str) -> Result<bool, String> {
+    s.parse().map_err(|_| format!("expected 'true' or 'false', found '{s}'"))
+}
+
+fn parse_usize(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|_| format!("expected a number, found '{s}'"))
+}
+
+fn parse_dirs(s: &str) -> Result<RewriteDirections, String> {
+    match s {
+        "none"     => Ok(RewriteDirections::None),
+        "forward"  => Ok(RewriteDirections::Forward),
+        "backward" => Ok(RewriteDirections::Backward),
+        "both"     => Ok(RewriteDirections::Both),
+        _          => Err(format!("expected rewrite directions, found '{s}'"))
+    }
+}
+
+fn set_option(cfg: &mut Config, key: &str, val: &str) -> Result<(), String> {
+    match key {
+        "optimize_expl"          => cfg.optimize_expl          = parse_bool(val)?,
+        "time_limit"             => cfg.time_limit             = parse_usize(val)?,
+        "node_limit"             => cfg.node_limit             = parse_usize(val)?,
+        "iter_limit"             => cfg.iter_limit             = parse_usize(val)?,
+        "nat_lit"                => cfg.nat_lit                = parse_bool(val)?,
+        "eta"                    => cfg.eta                    = parse_bool(val)?,
+        "eta_expand"             => cfg.eta_expand             = parse_bool(val)?,
+        "beta"                   => cfg.beta                   = parse_bool(val)?,
+        "levels"                 => cfg.levels                 = parse_bool(val)?,
+        "shapes"                 => cfg.shapes                 = parse_bool(val)?,
+        "block_invalid_matches"  => cfg.block_invalid_matches  = parse_bool(val)?,
+        "shift_captured_bvars"   => cfg.shift_captured_bvars   = parse_bool(val)?,
+        "union_semantics"        => cfg.union_semantics        = parse_bool(val)?,
+        "allow_unsat_conditions" => cfg.allow_unsat_conditions = parse_bool(val)?,
+        _                        => return Err(format!("unknown config option '{key}'"))
+    }
+    Ok(())
+}
+
+fn add_to_encoding(enc: &mut Encoding, key: &str, val: String) -> Result<(), String> {
+    match key {
+        "init"    => enc.init = val,
+        "goal"    => enc.goal = val,
+        "guide"   => enc.guides.push(val),
+        "fact"    => enc.facts.push((val, String::new())),
+        "prop"    => {
+            let Some(fact) = enc.facts.last_mut() else { return Err("'prop' without preceding 'fact'".to_string()) };
+            fact.1 = val
+        },
+        "rewrite" => {
+            let (dirs, name) = val.split_once(' ').unwrap_or((&val, ""));
+            enc.rws.push(Rewrite { name: name.to_string(), dirs: parse_dirs(dirs)?, ..Default::default() })
+        },
+        "lhs" | "rhs" | "cond" => {
+            let Some(rw) = enc.rws.last_mut() else { return Err(format!("'{key}' without preceding 'rewrite'")) };
+            match key {
+                "lhs" => rw.lhs = val,
+                "rhs" => rw.rhs = val,
+                _     => rw.conds.push(val)
+            }
+        },
+        _ => return Err(format!("unknown keyword '{key}'"))
+    }
+    Ok(())
+}
+
+fn check_encoding(enc: &Encoding, section: &str) -> Result<(), String> {
+    if enc.init.is_empty() { return Err(format!("[{section}] is missing 'init'")) }
+    if enc.goal.is_empty() { return Err(format!("[{section}] is missing 'goal'")) }
+    for rw in &enc.rws {
+        if rw.lhs.is_empty() || rw.rhs.is_empty() {
+            return Err(format!("rewrite '{}' in [{section}] is missing 'lhs' or 'rhs'", rw.name))
+        }
+    }
+    for (name, prop) in &enc.facts {
+        if prop.is_empty() { return Err(format!("fact '{name}' in [{section}] is missing 'prop'")) }
+    }
+    Ok(())
+}
+
+pub fn parse(text: &str) -> Result<Request, String> {
+    let mut cfg     = Config::default();
+    let mut egg     = Encoding::default();
+    let mut slotted = Encoding::default();
+    let mut section = Section::None;
+
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") { continue }
+
+        let res = match line {
+            "[config]"  => { section = Section::Config;  Ok(()) },
+            "[egg]"     => { section = Section::Egg;     Ok(()) },
+            "[slotted]" => { section = Section::Slotted; Ok(()) },
+            _ => {
+                let (key, val) = line.split_once(' ').unwrap_or((line, ""));
+                match section {
+                    Section::None    => Err("expected a section header".to_string()),
+                    Section::Config  => set_option(&mut cfg, key, val.trim()),
+                    Section::Egg     => add_to_encoding(&mut egg, key, unescape(val)),
+                    Section::Slotted => add_to_encoding(&mut slotted, key, unescape(val))
+                }
+            }
+        };
+        res.map_err(|err| format!("line {}: {err}", idx + 1))?;
+    }
+
+    check_encoding(&egg, "egg")?;
+    check_encoding(&slotted, "slotted")?;
+    Ok(Request { cfg, egg, slotted })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cf. `escape` in `Egg.Core.Request.Export`.
+    fn escape(s: &str) -> String {
+        s.replace('\\', "\\\\").replace('\n', "\\n")
+    }
+
+    #[test]
+    fn unescape_inverts_export_escape() {
+        for s in ["(const \"a\\b\")", "line\nbreak", "\\n", "\\\n\\", "\\\\n", ""] {
+            assert_eq!(unescape(&escape(s)), s);
+        }
+    }
+
+    #[test]
+    fn parses_all_sections() {
+        let text = "\
+            -- Test:1\n\
+            [config]\n\
+            time_limit 5\n\
+            shapes true\n\
+            [egg]\n\
+            init (fvar 1)\n\
+            goal (fvar 2)\n\
+            rewrite backward h\n\
+            lhs (fvar 1)\n\
+            rhs (fvar 2)\n\
+            cond ?1\n\
+            fact h₁\n\
+            prop (const \"a\\nb\")\n\
+            guide (fvar 3)\n\
+            [slotted]\n\
+            init (fvar 1)\n\
+            goal (fvar 2)\n";
+        let req = parse(text).unwrap();
+        assert_eq!(req.cfg.time_limit, 5);
+        assert!(req.cfg.shapes);
+        assert!(req.cfg.beta);
+        assert_eq!(req.egg.rws.len(), 1);
+        assert_eq!(req.egg.rws[0].name, "h");
+        assert_eq!(req.egg.rws[0].dirs, RewriteDirections::Backward);
+        assert_eq!(req.egg.rws[0].conds, vec!["?1"]);
+        assert_eq!(req.egg.facts, vec![("h₁".to_string(), "(const \"a\nb\")".to_string())]);
+        assert_eq!(req.egg.guides, vec!["(fvar 3)"]);
+        assert!(req.slotted.rws.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let errors = [
+            ("init (fvar 1)",                                                "line 1: expected a section header"),
+            ("[config]\nfoo true",                                           "line 2: unknown config option 'foo'"),
+            ("[config]\nbeta 1",                                             "line 2: expected 'true' or 'false', found '1'"),
+            ("[egg]\nlhs (fvar 1)",                                          "line 2: 'lhs' without preceding 'rewrite'"),
+            ("[egg]\nrewrite sideways h",                                    "line 2: expected rewrite directions, found 'sideways'"),
+            ("[egg]\ninit (fvar 1)",                                         "[egg] is missing 'goal'"),
+            ("[egg]\ninit (fvar 1)\ngoal (fvar 1)\n[slotted]\ngoal (fvar 1)", "[slotted] is missing 'init'"),
+        ];
+        for (text, err) in errors {
+            assert_eq!(parse(text).err().as_deref(), Some(err));
+        }
+    }
+
+    #[test]
+    fn parses_corpus() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("requests");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let text = std::fs::read_to_string(&path).unwrap();
+            assert!(parse(&text).is_ok(), "{}", path.display());
+        }
+    }
+}
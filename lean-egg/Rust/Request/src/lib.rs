@@ -0,0 +1,85 @@
+// The parts of a request which don't depend on the backend. These are shared by the egg and slotted
+// backends, and by the parity harness in `Rust/Parity`, which replays requests on both backends.
+
+// IMPORTANT: The C interface depends on the order of these fields.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Config {
+    pub optimize_expl:          bool,
+    pub time_limit:             usize,
+    pub node_limit:             usize,
+    pub iter_limit:             usize,
+    pub nat_lit:                bool,
+    pub eta:                    bool,
+    pub eta_expand:             bool,
+    pub beta:                   bool,
+    pub levels:                 bool,
+    pub shapes:                 bool,
+    pub block_invalid_matches:  bool,
+    pub shift_captured_bvars:   bool,
+    pub union_semantics:        bool,
+    pub allow_unsat_conditions: bool
+}
+
+// Cf. `Egg.Config` on the Lean side.
+impl Default for Config {
+
+    fn default() -> Config {
+        Config {
+            optimize_expl:          true,
+            time_limit:             30,
+            node_limit:             1000000000000000000,
+            iter_limit:             1000000000000000000,
+            nat_lit:                true,
+            eta:                    true,
+            eta_expand:             false,
+            beta:                   true,
+            levels:                 true,
+            shapes:                 false,
+            block_invalid_matches:  true,
+            shift_captured_bvars:   true,
+            union_semantics:        true,
+            allow_unsat_conditions: false
+        }
+    }
+}
+
+// IMPORTANT: The C interface depends on the order of these variants.
+#[repr(C)]
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum RewriteDirections {
+    #[default]
+    None,
+    Forward,
+    Backward,
+    Both
+}
+
+#[derive(Default)]
+pub struct Rewrite {
+    pub name:  String,
+    pub lhs:   String,
+    pub rhs:   String,
+    pub dirs:  RewriteDirections,
+    pub conds: Vec<String>
+}
+
+// A request in a backend's encoding, which can be replayed without Lean.
+#[derive(Default)]
+pub struct Encoding {
+    pub init:   String,
+    pub goal:   String,
+    pub rws:    Vec<Rewrite>,
+    pub facts:  Vec<(String, String)>,
+    pub guides: Vec<String>
+}
+
+// The parts of a replayed request's result which are comparable across backends.
+pub struct Outcome {
+    pub expl:        String, // One term per line, or the empty string if the goal was not proven.
+    pub iterations:  usize,  // The number of rounds in which rewrites were applied.
+    pub stop_reason: String,
+    pub nodes:       usize,
+    pub classes:     usize,
+    pub time:        f64
+}
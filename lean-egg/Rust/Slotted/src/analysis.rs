@@ -1,9 +1,12 @@
 use slotted_egraphs::*;
 use crate::lean_expr::*;
+use crate::levels::*;
 
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct LeanAnalysis {
     pub nat_val: Option<u64>,
+    pub str_val: Option<String>,
+    pub level: Option<Level>, // The normal form of the class, if it represents a universe level.
     pub is_primitive: bool // A class is primitive if it represents a `Nat`, `Str` or universe level e-node.
 }
 
@@ -12,17 +15,50 @@ impl Analysis<LeanExpr> for LeanAnalysis {
     fn merge(l: Self, r: Self) -> Self {       
         Self {
             nat_val: l.nat_val.max(r.nat_val),
+            str_val: l.str_val.or(r.str_val),
+            level: match (l.level, r.level) {
+                (Some(l), Some(r)) => Some(l.min(r)),
+                (l, r)             => l.or(r)
+            },
             is_primitive: l.is_primitive && r.is_primitive
         }
     }
 
-    fn make(_: &EGraph<LeanExpr, Self>, enode: &LeanExpr) -> Self {      
+    fn make(eg: &EGraph<LeanExpr, Self>, enode: &LeanExpr) -> Self {      
+        let level = |i: &AppliedId| { let data: &Self = eg.analysis_data(i.id); data.level.clone() };
         match enode {
-            LeanExpr::Nat(n) => Self { nat_val: Some(*n), is_primitive: true },
-            
-            LeanExpr::Str(_) | LeanExpr::UVar(_) | LeanExpr::Param(_) | LeanExpr::Succ(_) | 
-            LeanExpr::Max(_, _) | LeanExpr::IMax(_, _) | LeanExpr::Unknown => 
-                Self { is_primitive: true, ..Default::default() },
+            // Zero is the only level which is encoded as a `Nat`.
+            LeanExpr::Nat(n) => Self { 
+                nat_val: Some(*n), 
+                level: if *n == 0 { Some(Level::constant(0)) } else { None }, 
+                is_primitive: true, 
+                ..Default::default() 
+            },
+
+            LeanExpr::Str(s) => Self { str_val: Some(s.clone()), is_primitive: true, ..Default::default() },
+
+            LeanExpr::UVar(i) => {
+                let data: &Self = eg.analysis_data(i.id);
+                let level = data.nat_val.map(|n| Level::atom(LevelAtom::UVar(n)));
+                Self { level, is_primitive: true, ..Default::default() }
+            },
+
+            LeanExpr::Param(i) => {
+                let data: &Self = eg.analysis_data(i.id);
+                let level = data.str_val.clone().map(|s| Level::atom(LevelAtom::Param(s)));
+                Self { level, is_primitive: true, ..Default::default() }
+            },
+
+            LeanExpr::Succ(i) => 
+                Self { level: level(i).and_then(|l| l.succ()), is_primitive: true, ..Default::default() },
+
+            LeanExpr::Max(i1, i2) => 
+                Self { level: level(i1).zip(level(i2)).map(|(l1, l2)| l1.max_with(&l2)), is_primitive: true, ..Default::default() },
+
+            LeanExpr::IMax(i1, i2) => 
+                Self { level: level(i1).zip(level(i2)).map(|(l1, l2)| l1.imax_with(&l2)), is_primitive: true, ..Default::default() },
+
+            LeanExpr::Star | LeanExpr::Unknown => Self { is_primitive: true, ..Default::default() },
 
             _ => Default::default()
         }
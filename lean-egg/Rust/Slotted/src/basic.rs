@@ -1,97 +1,187 @@
-use std::collections::HashMap;
 use slotted_egraphs::*;
 use crate::result::*;
 use crate::analysis::*;
+use crate::lean_expr::*;
 use crate::beta::*;
 use crate::eta::*;
 use crate::levels::*;
 use crate::nat_lit::*;
 use crate::rewrite::*;
+pub use lean_egg_request::Config;
 
-#[repr(C)]
-pub struct Config {
-    optimize_expl:          bool,
-    time_limit:             usize,
-    node_limit:             usize,
-    iter_limit:             usize, 
-    nat_lit:                bool, 
-    eta:                    bool,
-    eta_expand:             bool,
-    beta:                   bool,
-    levels:                 bool,
-    shapes:                 bool,
-    block_invalid_matches:  bool,
-    shift_captured_bvars:   bool,
-    union_semantics:        bool,
-    allow_unsat_conditions: bool
+// A session keeps an e-graph alive across multiple requests, so that the rewrites and facts only
+// need to be parsed once. Goals and guides can be added incrementally, and saturation can be
+// resumed by calling `saturate` again.
+pub struct Session {
+    pub egraph: LeanEGraph,
+    rws:        Vec<LeanRewrite>,
+    goals:      Vec<(RecExpr<LeanExpr>, RecExpr<LeanExpr>, AppliedId, AppliedId)>,
+    cfg:        Config
 }
 
-pub fn explain_congr(
-    init: String, goal: String, rw_templates: Vec<RewriteTemplate>, facts: Vec<(String, String)>, 
-    guides: Vec<String>, cfg: Config, _viz_path: Option<String>
-) -> Result<(String, LeanEGraph, Report), Error> {    
-    let mut egraph: LeanEGraph = EGraph::new();
+impl Session {
 
-    let init_expr = RecExpr::parse(&init).map_err(|err| {
-        Error::Init(format!("Failed to parse lhs of goal: {:?}\n\n  {}", err, init).to_string())
-    })?;
-    let goal_expr = RecExpr::parse(&goal).map_err(|err| {
-        Error::Goal(format!("Failed to parse rhs of goal: {:?}\n\n  {}", err, goal).to_string())
-    })?;
-    let init_id = egraph.add_expr(init_expr.clone());
-    let goal_id = egraph.add_expr(goal_expr.clone());
+    pub fn new(rw_templates: Vec<RewriteTemplate>, facts: Vec<(String, String)>, cfg: Config) -> Res<Session> {
+        let mut egraph: LeanEGraph = EGraph::new();
 
-    for guide in guides {
-        let expr = RecExpr::parse(&guide).map_err(|err| {
-            Error::Guide(format!("Failed to parse guide term: {:?}\n\n  {}", err, guide).to_string())
-    })?;
-        egraph.add_expr(expr);
+        for (name, expr) in facts {
+            let expr = RecExpr::parse(&expr).map_err(|err| {
+                Error::Fact(format!("Failed to parse fact: {}\n\n  {}", err, expr).to_string())
+            })?;
+            let class = egraph.add_expr(expr);
+            egraph.add_fact(&class, name);
+        }
+
+        // Note: `cfg.union_semantics` does not have an effect on slotted e-graphs. In egg, it determines
+        //       how the loose bvars of merged e-classes are approximated. Slotted e-graphs instead 
+        //       track the exact set of slots used by each e-class, so no approximation is necessary.
+        let mut rws;
+        match templates_to_rewrites(rw_templates, cfg.block_invalid_matches, cfg.shift_captured_bvars, cfg.allow_unsat_conditions) {
+            Ok(r)    => rws = r,
+            Err(err) => return Err(Error::Rewrite(err.to_string()))
+        }
+        if cfg.nat_lit    { rws.append(&mut nat_lit_rws(cfg.shapes)) }
+        if cfg.eta        { rws.push(eta_reduction_rw()) }
+        if cfg.eta_expand { rws.push(eta_expansion_rw()) }
+        if cfg.beta       { rws.append(&mut beta_reduction_rws(/*small_step:*/false)) }
+        if cfg.levels     { rws.append(&mut level_rws()) }
+
+        Ok(Session { egraph, rws, goals: vec![], cfg })
+    }
+
+    // Returns the index of the new goal, which can be passed to `explain_goal`.
+    pub fn add_goal(&mut self, init: String, goal: String) -> Res<usize> {
+        let init_expr = RecExpr::parse(&init).map_err(|err| {
+            Error::Init(format!("Failed to parse lhs of goal: {}\n\n  {}", err, init).to_string())
+        })?;
+        let goal_expr = RecExpr::parse(&goal).map_err(|err| {
+            Error::Goal(format!("Failed to parse rhs of goal: {}\n\n  {}", err, goal).to_string())
+        })?;
+        let init_id = self.egraph.add_expr(init_expr.clone());
+        let goal_id = self.egraph.add_expr(goal_expr.clone());
+        self.goals.push((init_expr, goal_expr, init_id, goal_id));
+        Ok(self.goals.len() - 1)
     }
 
-    let mut fact_map: HashMap<AppliedId, String> = Default::default();
-    for (name, expr) in facts {
-        let expr = RecExpr::parse(&expr).map_err(|err| {
-            Error::Fact(format!("Failed to parse fact: {:?}\n\n  {}", err, expr).to_string())
+    pub fn add_guide(&mut self, guide: String) -> Res<()> {
+        let expr = RecExpr::parse(&guide).map_err(|err| {
+            Error::Guide(format!("Failed to parse guide term: {}\n\n  {}", err, guide).to_string())
         })?;
-        let class = egraph.add_expr(expr);
-        fact_map.insert(class, name);
+        self.egraph.add_expr(expr);
+        Ok(())
     }
 
-    let mut rws;
-    match templates_to_rewrites(rw_templates, fact_map, cfg.allow_unsat_conditions) {
-        Ok(r)    => rws = r,
-        Err(err) => return Err(Error::Rewrite(err.to_string()))
+    // Runs equality saturation until all goals are proven or a limit is reached. The limits apply 
+    // to each call of `saturate` individually.
+    pub fn saturate(&mut self) -> Report {
+        let goals = Goals::all(self.goals.iter().map(|(_, _, i, g)| Goal::Equal(i.clone(), g.clone())).collect());
+        run_eqsat_goals(&mut self.egraph, &self.rws, &goals, Limits::new(self.cfg.iter_limit, self.cfg.time_limit), |_| Ok(()))
     }
-    if cfg.nat_lit    { rws.append(&mut nat_lit_rws()) }
-    if cfg.eta        { rws.push(eta_reduction_rw()) }
-    if cfg.eta_expand { rws.push(eta_expansion_rw()) }
-    if cfg.beta       { rws.append(&mut beta_reduction_rws(/*small_step:*/false)) }
-    if cfg.levels     { rws.append(&mut level_rws()) }
-
-    let i = init_id.clone();
-    let g = goal_id.clone();
-    let report = run_eqsat(&mut egraph, rws, cfg.iter_limit, cfg.time_limit, move |egraph| {
-        if egraph.eq(&i, &g) {
-            Err("proved goal".to_string())
+
+    // Returns the explanation of the given goal, or `None` if it has not been proven (yet).
+    pub fn explain_goal(&mut self, idx: usize) -> Res<Option<FlatExplanation<LeanExpr>>> {
+        let Some((init_expr, goal_expr, init_id, goal_id)) = self.goals.get(idx).cloned() else {
+            return Err(Error::Goal(format!("Unknown goal index {idx}")))
+        };
+        if self.egraph.eq(&init_id, &goal_id) {
+            Ok(Some(explain(&mut self.egraph, init_expr, goal_expr, self.cfg.optimize_expl)))
         } else {
-            Ok(())
+            Ok(None)
         }
-    });
+    }
+
+    pub fn query_equiv(&mut self, init: String, goal: String) -> Res<Option<FlatExplanation<LeanExpr>>> {
+        query_equiv(&mut self.egraph, init, goal, self.cfg.optimize_expl)
+    }
+}
+
+fn explain(egraph: &mut LeanEGraph, init: RecExpr<LeanExpr>, goal: RecExpr<LeanExpr>, optimize: bool) -> FlatExplanation<LeanExpr> {
+    proven_eq(egraph, init, goal, optimize).to_flat_explanation(egraph)
+}
+
+fn proven_eq(egraph: &mut LeanEGraph, init: RecExpr<LeanExpr>, goal: RecExpr<LeanExpr>, optimize: bool) -> ProvenEq {
+    if optimize {
+        egraph.explain_equivalence_minimized(init, goal)
+    } else {
+        egraph.explain_equivalence(init, goal)
+    }
+}
+
+// Returns the proof of `init = goal`, or `None` if they are not equivalent.
+fn query(egraph: &mut LeanEGraph, init: String, goal: String, optimize: bool) -> Res<Option<ProvenEq>> {
+    let init = RecExpr::parse(&init).map_err(|err| {
+        Error::Init(format!("Failed to parse lhs of query: {err}"))
+    })?;
+    let goal = RecExpr::parse(&goal).map_err(|err| {
+        Error::Goal(format!("Failed to parse rhs of query: {err}"))
+    })?;
+    let init_id = egraph.add_expr(init.clone());
+    let goal_id = egraph.add_expr(goal.clone());
 
     if egraph.eq(&init_id, &goal_id) {
-        let expl = egraph.explain_equivalence(init_expr, goal_expr);
-
-        // HACK: For debugging purposes we're abusing the `optimize_expl` option to indicate whether 
-        //       the tree explanation should be returned instead of a flat explanation. A value of 
-        //       `false` indicates that the tree explanation should be returned.
-        if !cfg.optimize_expl {
-            let tree_expl = expl.to_string(&egraph);
-            Ok((tree_expl, egraph, report))
-        } else {
-            let flat_expl = expl.to_flat_string(&egraph);
-            Ok((flat_expl, egraph, report))
-        }
+        Ok(Some(proven_eq(egraph, init, goal, optimize)))
     } else {
-        Ok(("".to_string(), egraph, report))
+        Ok(None)
+    }
+}
+
+// Returns the explanation of `init = goal`, or `None` if they are not equivalent.
+pub fn query_equiv(egraph: &mut LeanEGraph, init: String, goal: String, optimize: bool) -> Res<Option<FlatExplanation<LeanExpr>>> {
+    let prf = query(egraph, init, goal, optimize)?;
+    Ok(prf.map(|prf| prf.to_flat_explanation(egraph)))
+}
+
+pub fn explain_congr(
+    init: String, goal: String, rw_templates: Vec<RewriteTemplate>, facts: Vec<(String, String)>, 
+    guides: Vec<String>, cfg: Config, _viz_path: Option<String>
+) -> Result<(Option<FlatExplanation<LeanExpr>>, LeanEGraph, Report), Error> {    
+    let mut session = Session::new(rw_templates, facts, cfg)?;
+    let goal_idx = session.add_goal(init, goal)?;
+    for guide in guides {
+        session.add_guide(guide)?;
     }
-}
\ No newline at end of file
+
+    let report = session.saturate();
+    let expl = session.explain_goal(goal_idx)?;
+    Ok((expl, session.egraph, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RewriteDirections;
+    use super::*;
+
+    fn cfg() -> Config {
+        Config {
+            optimize_expl: false, time_limit: 60, node_limit: usize::MAX, iter_limit: 10, nat_lit: false, 
+            eta: false, eta_expand: false, beta: false, levels: false, shapes: false, 
+            block_invalid_matches: false, shift_captured_bvars: false, union_semantics: false, 
+            allow_unsat_conditions: false
+        }
+    }
+
+    fn f(a: &str, b: &str) -> String {
+        format!("(app (app (const \"f\") (const \"{a}\")) (const \"{b}\"))")
+    }
+
+    #[test]
+    fn session_is_reused_across_goals() {
+        let comm = parse_templates("comm", "(app (app (const \"f\") ?a) ?b)", "(app (app (const \"f\") ?b) ?a)", &[], &RewriteDirections::Forward).unwrap();
+        let mut session = Session::new(comm, vec![], cfg()).unwrap();
+
+        let first = session.add_goal(f("a", "b"), f("b", "a")).unwrap();
+        assert!(matches!(session.saturate().stop_reason, StopReason::GoalsReached));
+        assert!(session.explain_goal(first).unwrap().is_some());
+
+        let second = session.add_goal(f("c", "d"), f("d", "c")).unwrap();
+        assert_eq!(second, 1);
+        assert!(session.explain_goal(second).unwrap().is_none());
+        assert!(matches!(session.saturate().stop_reason, StopReason::GoalsReached));
+        assert!(session.explain_goal(second).unwrap().is_some());
+        assert!(session.explain_goal(first).unwrap().is_some());
+        assert!(session.explain_goal(2).is_err());
+
+        assert!(session.query_equiv(f("a", "b"), f("b", "a")).unwrap().is_some());
+        assert!(session.query_equiv(f("a", "b"), f("a", "c")).unwrap().is_none());
+    }
+}
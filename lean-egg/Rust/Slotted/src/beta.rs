@@ -4,7 +4,7 @@ use crate::analysis::*;
 pub fn beta_reduction_rws(small_step: bool) -> Vec<LeanRewrite> {
     if small_step {
         let mut rws = vec![];
-        rws.push(Rewrite::new("≡β", "(app (λ $0 ?t ?b) ?a)", "(↦ $0 ?a ?b)"));
+        rws.push(small_step_beta_rw());
         rws.append(&mut subst_rws());
         rws
     } else {
@@ -12,15 +12,36 @@ pub fn beta_reduction_rws(small_step: bool) -> Vec<LeanRewrite> {
     }
 }
 
+// The slot of `↦` is public, so `Rewrite::new` would reject the rhs for mentioning `$0` freely.
+// This is intended here: the slot only disappears once the substitution is fully propagated.
+fn small_step_beta_rw() -> LeanRewrite {
+    Rewrite::new_unchecked("≡β", "(app (λ $0 ?t ?b) ?a)", "(↦ $0 ?a ?b)")
+}
+
 fn subst_rws() -> Vec<LeanRewrite> {
     let mut rws = vec![];
-    rws.push(Rewrite::new("↦bvar", "(↦ $x ?z (bvar $x))",    "?z"));
-    rws.push(Rewrite::new("↦app",  "(↦ $x ?z (app ?a ?b))",  "(app (↦ $x ?z ?a) (↦ $x ?z ?b))"));
-    rws.push(Rewrite::new("↦λ",    "(↦ $x ?z (λ $y ?t ?b))", "(λ $y (↦ $x ?z ?t) (↦ $x ?z ?b))"));
-    rws.push(Rewrite::new("↦∀",    "(↦ $x ?z (∀ $y ?t ?b))", "(∀ $y (↦ $x ?z ?t) (↦ $x ?z ?b))"));
-    rws.push(Rewrite::new_if("↦|", "(↦ $x ?z ?e)", "?e", |subst, _| { 
-        !subst["e"].slots().contains(&Slot::named("x"))
-    }));
-    // TODO: We don't propagate substitutions over erased proofs at the moment,
+    rws.push(Rewrite::new("↦bvar",  "(↦ $x ?z (bvar $x))",    "?z"));
+    rws.push(Rewrite::new("↦app",   "(↦ $x ?z (app ?a ?b))",  "(app (↦ $x ?z ?a) (↦ $x ?z ?b))"));
+    rws.push(Rewrite::new("↦λ",     "(↦ $x ?z (λ $y ?t ?b))", "(λ $y (↦ $x ?z ?t) (↦ $x ?z ?b))"));
+    rws.push(Rewrite::new("↦∀",     "(↦ $x ?z (∀ $y ?t ?b))", "(∀ $y (↦ $x ?z ?t) (↦ $x ?z ?b))"));
+    rws.push(Rewrite::new("↦proof", "(↦ $x ?z (proof ?p))",   "(proof (↦ $x ?z ?p))"));
+    rws.push(Rewrite::new("↦|",     "(↦ $x ?z ?e[-$x])",      "?e"));
     rws
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The big-step rule substitutes into proofs directly, so only the small-step rules need `↦proof`.
+    #[test]
+    fn small_step_beta_substitutes_into_proofs() {
+        let mut eg = LeanEGraph::new();
+        let start = RecExpr::parse("(app (λ $0 (const \"Nat\") (proof (app (const \"P\") (bvar $0)))) (const \"a\"))").unwrap();
+        let goal = RecExpr::parse("(proof (app (const \"P\") (const \"a\")))").unwrap();
+        let i = eg.add_expr(start);
+        let goals = Goals::any(vec![Goal::Term(i, goal)]);
+        let report = run_eqsat_goals(&mut eg, &beta_reduction_rws(true), &goals, Limits::new(10, 60), |_| Ok(()));
+        assert!(matches!(report.stop_reason, StopReason::GoalsReached));
+    }
+}
@@ -26,14 +26,17 @@ pub enum LeanExpr {
     Lit(AppliedId),                     // (Nat | Str)
 
     // Construct for proof erasure:
-    Proof(AppliedId),
+    // A proof is encoded by its type only, so by congruence, two proofs are equal whenever their 
+    // types are. This is exactly proof irrelevance.
+    Proof(AppliedId),                   // (<expr>)
 
     // Construct for small-step substitution:
     Subst(Slot, AppliedId, AppliedId),  // (<var>, <expr>, <expr>)
 
     // Constructs for shape annotations:
-    // Note, we don't encode the shape of non-function types explicitly and use `Str("*")`) for that 
-    // instead.
+    // Note, the shape of non-function types has its own node, so that it is not equal to the string 
+    // literal `"*"`.
+    Star,                               // ()
     Fun(AppliedId, AppliedId),          // (<shape>, <shape>)
     Shaped(AppliedId, AppliedId),       // (<shape>, <expr>)
 
@@ -132,6 +135,7 @@ impl Language for LeanExpr {
             LeanExpr::Lit(c)             => ("lit".to_string(), vec![Child::AppliedId(c)]),
             LeanExpr::Proof(c)           => ("proof".to_string(), vec![Child::AppliedId(c)]),
             LeanExpr::Subst(c1, c2, c3)  => ("↦".to_string(), vec![Child::Slot(c1), Child::AppliedId(c2), Child::AppliedId(c3)]),
+            LeanExpr::Star               => ("*".to_string(), vec![]),
             LeanExpr::Fun(c1, c2)        => ("→".to_string(), vec![Child::AppliedId(c1), Child::AppliedId(c2)]),
             LeanExpr::Shaped(c1, c2)     => ("◇".to_string(), vec![Child::AppliedId(c1), Child::AppliedId(c2)]),
             LeanExpr::Unknown            => ("_".to_string(), vec![]),
@@ -163,6 +167,7 @@ impl Language for LeanExpr {
             ("→",     [Child::AppliedId(c1), Child::AppliedId(c2)])                  => Some(LeanExpr::Fun(c1.clone(), c2.clone())),     
             ("◇",     [Child::AppliedId(c1), Child::AppliedId(c2)])                  => Some(LeanExpr::Shaped(c1.clone(), c2.clone())),     
             ("_",     [])                                                            => Some(LeanExpr::Unknown),     
+            ("*",     [])                                                            => Some(LeanExpr::Star),     
             ("const", cs) => {
                 let mut is = vec![];
                 for c in cs.iter() {
@@ -186,4 +191,23 @@ impl Language for LeanExpr {
             (op, cs) => panic!("Failed to parse ctor {} with {} children", op, cs.len())
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::analysis::*;
+    use super::*;
+
+    #[test]
+    fn proofs_of_equal_types_are_equal() {
+        let mut eg = LeanEGraph::new();
+        let p1 = eg.add_expr(RecExpr::parse("(proof (app (const \"P\") (const \"a\")))").unwrap());
+        let p2 = eg.add_expr(RecExpr::parse("(proof (app (const \"P\") (const \"b\")))").unwrap());
+        assert!(!eg.eq(&p1, &p2));
+
+        let a = eg.add_expr(RecExpr::parse("(const \"a\")").unwrap());
+        let b = eg.add_expr(RecExpr::parse("(const \"b\")").unwrap());
+        eg.union(&a, &b);
+        eg.rebuild();
+        assert!(eg.eq(&p1, &p2));
+    }
+}
@@ -1,12 +1,184 @@
+use std::collections::{BTreeMap, HashMap};
 use slotted_egraphs::*;
 use crate::analysis::*;
 
+// A universe level in normal form, denoting the maximum of `offset` and all `atom + k` in `terms`.
+// The offset is only kept if it exceeds all `k`, and is `0` otherwise. As a result, two levels
+// with the same normal form are definitionally equal.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Level {
+    terms:  BTreeMap<LevelAtom, u64>,
+    offset: u64
+}
+
+// The parts of a level which are not reduced any further. `IMax(l, a)` denotes `imax l a`, which
+// can only be simplified once it is known whether `a` is zero.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum LevelAtom {
+    UVar(u64),
+    Param(String),
+    IMax(Box<Level>, Box<LevelAtom>)
+}
+
+impl Level {
+
+    pub fn constant(n: u64) -> Self {
+        Self { terms: BTreeMap::new(), offset: n }
+    }
+
+    pub fn atom(a: LevelAtom) -> Self {
+        Self { terms: BTreeMap::from([(a, 0)]), offset: 0 }
+    }
+
+    fn normalize(mut self) -> Self {
+        if self.terms.values().any(|k| *k >= self.offset) { self.offset = 0 }
+        self
+    }
+
+    fn is_zero(&self) -> bool {
+        self.terms.is_empty() && self.offset == 0
+    }
+
+    fn is_never_zero(&self) -> bool {
+        self.offset > 0 || self.terms.values().any(|k| *k > 0)
+    }
+
+    pub fn succ(&self) -> Option<Self> {
+        let terms = self.terms.iter().map(|(a, k)| Some((a.clone(), k.checked_add(1)?))).collect::<Option<_>>()?;
+        Some(Self { terms, offset: self.offset.checked_add(1)? }.normalize())
+    }
+
+    pub fn max_with(&self, other: &Self) -> Self {
+        let mut terms = self.terms.clone();
+        for (a, k) in &other.terms {
+            let entry = terms.entry(a.clone()).or_insert(*k);
+            *entry = (*entry).max(*k);
+        }
+        Self { terms, offset: self.offset.max(other.offset) }.normalize()
+    }
+
+    // `imax l r` is `0` if `r` is `0`, and `max l r` otherwise. If neither is known, we use that
+    // `imax l (max r₁ r₂) = max (imax l r₁) (imax l r₂)` to split `r` into its atoms.
+    pub fn imax_with(&self, other: &Self) -> Self {
+        if other.is_zero() || self.is_zero() { return other.clone() }
+        if other.is_never_zero() { return self.max_with(other) }
+        let mut res = Self::constant(0);
+        for a in other.terms.keys() {
+            let atom = Self::atom(a.clone());
+            let imax = if *self == atom { atom } else { Self::atom(LevelAtom::IMax(Box::new(self.clone()), Box::new(a.clone()))) };
+            res = res.max_with(&imax);
+        }
+        res
+    }
+}
+
+// Unions all classes whose levels have the same normal form. As this subsumes the individual rules
+// for `max` and `imax`, those are not needed in the slotted backend.
+fn normalization_rw() -> LeanRewrite {
+    RewriteT {
+        searcher: Box::new(|graph| {
+            let mut classes: HashMap<Level, Vec<Id>> = HashMap::new();
+            for id in graph.ids() {
+                let analysis: &LeanAnalysis = graph.analysis_data(id);
+                let Some(level) = &analysis.level else { continue };
+                if !graph.slots(id).is_empty() { continue }
+                classes.entry(level.clone()).or_default().push(id);
+            }
+            classes.into_values().filter(|ids| ids.len() > 1).collect::<Vec<_>>()
+        }),
+        applier: Box::new(|groups, graph| {
+            for ids in groups {
+                let first = AppliedId::new(ids[0], SlotMap::new());
+                for id in &ids[1..] {
+                    let other = AppliedId::new(*id, SlotMap::new());
+                    graph.union_justified(&first, &other, Some("≡lvl".to_string()));
+                }
+            }
+        }),
+    }.into()
+}
+
 pub fn level_rws() -> Vec<LeanRewrite> {
-    let mut rws = vec![];
-    rws.push(Rewrite::new("≡maxS", "(max (succ ?a) (succ ?b))", "(succ (max ?b ?a))"));
-    rws.push(Rewrite::new("≡maxS-rev", "(succ (max ?b ?a))", "(max (succ ?a) (succ ?b))"));
-    rws.push(Rewrite::new("≡max↔", "(max ?a ?b)", "(max ?b ?a)"));
-    rws.push(Rewrite::new("≡imax0", "(imax ?a 0)", "0"));
-    rws.push(Rewrite::new("≡imaxS", "(imax ?a (succ ?b))", "(max ?a (succ ?b))"));
-    rws
-}
\ No newline at end of file
+    vec![normalization_rw()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(s: &str) -> Level { Level::atom(LevelAtom::Param(s.to_string())) }
+    fn uvar(n: u64) -> Level { Level::atom(LevelAtom::UVar(n)) }
+    fn succ(l: &Level) -> Level { l.succ().unwrap() }
+
+    #[test]
+    fn max_normal_forms() {
+        let (u, v) = (param("u"), param("v"));
+        assert_eq!(u.max_with(&u), u);
+        assert_eq!(u.max_with(&v), v.max_with(&u));
+        assert_eq!(u.max_with(&uvar(0)), uvar(0).max_with(&u));
+        assert_eq!(succ(&u).max_with(&u), succ(&u));
+        assert_eq!(Level::constant(1).max_with(&Level::constant(3)), Level::constant(3));
+        assert_ne!(u.max_with(&v), u);
+    }
+
+    #[test]
+    fn succ_normal_forms() {
+        let (u, v) = (param("u"), param("v"));
+        assert_eq!(succ(&Level::constant(2)), Level::constant(3));
+        assert_eq!(succ(&u.max_with(&v)), succ(&u).max_with(&succ(&v)));
+        assert_eq!(succ(&u.max_with(&Level::constant(1))), succ(&u).max_with(&Level::constant(2)));
+        assert_eq!(Level::constant(u64::MAX).succ(), None);
+    }
+
+    // The offset is dropped as soon as some atom has at least the same offset.
+    #[test]
+    fn param_offsets() {
+        let u = param("u");
+        let u2 = succ(&succ(&u));
+        assert_eq!(u2.max_with(&succ(&u)), u2);
+        assert_eq!(u2.max_with(&Level::constant(2)), u2);
+        assert_eq!(u2.max_with(&Level::constant(1)), u2);
+        assert_ne!(u2.max_with(&Level::constant(3)), u2);
+        assert_eq!(u.max_with(&Level::constant(1)), succ(&Level::constant(0)).max_with(&u));
+        assert_ne!(u.max_with(&Level::constant(1)), u);
+        assert_ne!(succ(&u).max_with(&param("v")), succ(&u).max_with(&succ(&param("v"))));
+    }
+
+    #[test]
+    fn imax_normal_forms() {
+        let (u, v, w) = (param("u"), param("v"), param("w"));
+        let zero = Level::constant(0);
+        assert_eq!(u.imax_with(&u), u);
+        assert_eq!(zero.imax_with(&u), u);
+        assert_ne!(u.imax_with(&v), u.max_with(&v));
+        assert_eq!(u.imax_with(&v), Level::atom(LevelAtom::IMax(Box::new(u.clone()), Box::new(LevelAtom::Param("v".to_string())))));
+        assert_eq!(u.imax_with(&v.max_with(&w)), u.imax_with(&v).max_with(&u.imax_with(&w)));
+        assert_eq!(u.imax_with(&u.max_with(&v)), u.max_with(&u.imax_with(&v)));
+    }
+
+    #[test]
+    fn imax_with_zero_or_succ() {
+        let (u, v) = (param("u"), param("v"));
+        let zero = Level::constant(0);
+        assert_eq!(u.imax_with(&zero), zero);
+        assert_eq!(u.max_with(&v).imax_with(&zero), zero);
+        assert_eq!(u.imax_with(&succ(&v)), u.max_with(&succ(&v)));
+        assert_eq!(u.imax_with(&Level::constant(1)), u.max_with(&Level::constant(1)));
+        assert_eq!(u.imax_with(&succ(&v).max_with(&param("w"))), u.max_with(&succ(&v)).max_with(&param("w")));
+    }
+
+    #[test]
+    fn equal_normal_forms_share_a_class() {
+        let mut eg = LeanEGraph::new();
+        let start = RecExpr::parse("(imax (param \"u\") (max (succ 0) (param \"v\")))").unwrap();
+        let goal = RecExpr::parse("(max (succ (imax 0 0)) (max (param \"v\") (param \"u\")))").unwrap();
+        let a = eg.add_expr(start.clone());
+        let b = eg.add_expr(goal.clone());
+        assert!(!eg.eq(&a, &b));
+
+        apply_rewrites(&mut eg, &level_rws());
+        assert!(eg.eq(&a, &b));
+        let expl = eg.explain_equivalence(start, goal).to_flat_explanation(&eg);
+        assert!(expl.steps.iter().any(|step| step.rule.as_deref() == Some("≡lvl")));
+    }
+}
@@ -2,12 +2,16 @@ use slotted_egraphs::*;
 use core::ffi::c_char;
 use core::ffi::CStr;
 use std::ffi::CString;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::ptr::null;
+use std::sync::{Mutex, MutexGuard};
 use libc::c_double;
 use basic::*;
 use analysis::*;
+use lean_expr::*;
 use result::*;
 use rewrite::*;
+pub use lean_egg_request::RewriteDirections;
 
 mod analysis;
 mod basic;
@@ -16,6 +20,7 @@ mod eta;
 mod lean_expr;
 mod levels;
 mod nat_lit;
+pub mod replay;
 mod result;
 mod rewrite;
 
@@ -25,12 +30,33 @@ fn c_str_to_string(c_str: *const c_char) -> String {
     String::from_utf8_lossy(str.to_bytes()).to_string()
 }
 
-// TODO: I think this is a memory leak right now.
+// The returned string is owned by the caller, and has to be freed with `slotted_free_string`.
+// Interior nul bytes can't be represented in a C-string, so they are dropped.
 fn string_to_c_str(str: String) -> *const c_char {
-    let expl_c_str = CString::new(str).expect("conversion of Rust-string to C-string failed");
+    let expl_c_str = CString::new(str.replace('\0', "")).unwrap();
     expl_c_str.into_raw()
 }
 
+// Unwinding across an `extern "C"` function is undefined behavior. Thus, every exported function
+// runs its body via `catch_panic`, which turns a panic into an `Error::Panic`.
+fn catch_panic<T>(f: impl FnOnce() -> Res<T>) -> Res<T> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(res)      => res,
+        Err(payload) => {
+            let msg = 
+                if let Some(s) = payload.downcast_ref::<&str>() { s.to_string() } 
+                else if let Some(s) = payload.downcast_ref::<String>() { s.clone() } 
+                else { "unknown panic".to_string() };
+            Err(Error::Panic(msg))
+        }
+    }
+}
+
+// Panics while freeing can't be reported to the caller, so they are only caught.
+fn catch_panic_in_free(f: impl FnOnce()) {
+    let _ = catch_panic(|| { f(); Ok(()) });
+}
+
 #[repr(C)]
 pub struct CStringArray {
     ptr: *const *const c_char,
@@ -45,15 +71,6 @@ impl CStringArray {
     }
 }
 
-#[repr(C)]
-#[derive(PartialEq)]
-pub enum RewriteDirections {
-    None,
-    Forward,
-    Backward,
-    Both
-}
-
 #[repr(C)]
 pub struct CRewrite {
     name:  *const c_char,
@@ -83,21 +100,7 @@ impl CRewritesArray {
             let lhs_str       = lhs_c_str.to_str().unwrap();
             let rhs_str       = rhs_c_str.to_str().unwrap();
             let conds_strs    = rw.conds.to_vec();
-            let lhs           = Pattern::parse(lhs_str).expect("Failed to parse lhs");
-            let rhs           = Pattern::parse(rhs_str).expect("Failed to parse rhs");
-            let conds: Vec<_> = conds_strs.iter().map(|cond| Pattern::parse(cond).expect("Failed to parse condition")).collect();
-
-            if rw.dirs == RewriteDirections::Forward || rw.dirs == RewriteDirections::Both {
-                res.push(RewriteTemplate { name: name_str.to_string(), lhs: lhs.clone(), rhs: rhs.clone(), conds: conds.clone() })
-            }
-            if rw.dirs == RewriteDirections::Backward || rw.dirs == RewriteDirections::Both {
-                // It is important that we use the "-rev" suffix for reverse rules here, as this is also
-                // what's used for adding the reverse rule when using egg's `rewrite!(_; _ <=> _)` macro.
-                // If we choose another naming scheme, egg may complain about duplicate rules when 
-                // `rw.dir == RewriteDirection::Both`. This is the case, for example, for the rewrite
-                // `?a + ?b = ?b + ?a`.
-                res.push(RewriteTemplate { name: format!("{name_str}-rev"), lhs: rhs, rhs: lhs, conds })
-            }
+            res.extend(parse_templates(name_str, lhs_str, rhs_str, &conds_strs, &rw.dirs)?);
         }
         Ok(res)
     }
@@ -143,11 +146,17 @@ impl CStopReason {
             StopReason::Saturated      => CStopReason::Saturated,
             StopReason::IterationLimit => CStopReason::IterationLimit,
             StopReason::TimeLimit      => CStopReason::TimeLimit,
+            // The Lean side has no notion of a memory limit, and we don't set one.
+            StopReason::MemoryLimit    => CStopReason::Other,
+            // This matches the egg backend, which stops via a hook once the goal is proven.
+            StopReason::GoalsReached   => CStopReason::Other,
             StopReason::Other(_)       => CStopReason::Other,
         }
     }
 }
 
+// The `rw_stats` are owned by the caller, see `slotted_free_string`. They are null if the report
+// was created by `CReport::none`.
 #[repr(C)]
 pub struct CReport {
     iterations:     usize,
@@ -183,11 +192,164 @@ impl CReport {
     }
 }
 
+// A node of a `CTerm`. The `op` is as given by `LeanExpr::to_op`, `slot` is the name of the node's
+// slot (or the empty string if it has none), and `arity` is the number of its children.
+#[repr(C)]
+pub struct CTermNode {
+    op:    *const c_char,
+    slot:  *const c_char,
+    arity: usize
+}
+
+// A term as the array of its nodes in pre-order, i.e. each node is followed by its children.
+#[repr(C)]
+pub struct CTerm {
+    nodes: *const CTermNode,
+    len:   usize
+}
+
+impl CTerm {
+
+    fn empty() -> CTerm {
+        CTerm { nodes: null(), len: 0 }
+    }
+
+    fn from_expr(expr: &RecExpr<LeanExpr>) -> CTerm {
+        let mut nodes = vec![];
+        Self::push_nodes(expr, &mut nodes);
+        let nodes = nodes.into_boxed_slice();
+        CTerm { len: nodes.len(), nodes: Box::into_raw(nodes) as *const CTermNode }
+    }
+
+    fn push_nodes(expr: &RecExpr<LeanExpr>, nodes: &mut Vec<CTermNode>) {
+        let (op, children) = expr.node.to_op();
+        // Slots are displayed as `$<name>`, but Lean only needs the name.
+        let slot = children.iter().find_map(|child| match child {
+            Child::Slot(slot) => Some(slot.to_string().trim_start_matches('$').to_string()),
+            Child::AppliedId(_) => None
+        });
+        nodes.push(CTermNode { 
+            op:    string_to_c_str(op), 
+            slot:  string_to_c_str(slot.unwrap_or_default()), 
+            arity: expr.children.len() 
+        });
+        for child in &expr.children { Self::push_nodes(child, nodes) }
+    }
+
+    unsafe fn free(self) {
+        if self.nodes.is_null() { return }
+        let nodes = Box::from_raw(std::ptr::slice_from_raw_parts_mut(self.nodes as *mut CTermNode, self.len));
+        for node in nodes.iter() {
+            slotted_free_string(node.op as *mut c_char);
+            slotted_free_string(node.slot as *mut c_char);
+        }
+    }
+}
+
+// A single step of a `CExplanation`, cf. `FlatStep`.
+#[repr(C)]
+pub struct CExplStep {
+    rule:     *const c_char,  // The empty string, if the step has no justification.
+    backward: bool,
+    pos:      *const usize,   // The child indices leading from the root to the rewritten subterm.
+    pos_len:  usize,
+    dst:      CTerm,          // The whole term after this step.
+}
+
+// A structured explanation, cf. `FlatExplanation`. This has to be freed with `slotted_free_explanation`.
+//
+// If the explained terms are not equivalent, then `start` is empty.
+#[repr(C)]
+pub struct CExplanation {
+    start: CTerm,
+    steps: *const CExplStep,
+    len:   usize
+}
+
+impl CExplanation {
+
+    fn from_expl(expl: Option<FlatExplanation<LeanExpr>>) -> CExplanation {
+        let Some(expl) = expl else {
+            return CExplanation { start: CTerm::empty(), steps: null(), len: 0 }
+        };
+        let steps: Vec<CExplStep> = expl.steps.into_iter().map(|step| {
+            let pos = step.pos.into_boxed_slice();
+            CExplStep {
+                rule:     string_to_c_str(step.rule.unwrap_or_default()),
+                backward: step.backward,
+                pos_len:  pos.len(),
+                pos:      Box::into_raw(pos) as *const usize,
+                dst:      CTerm::from_expr(&step.dst),
+            }
+        }).collect();
+        let steps = steps.into_boxed_slice();
+        CExplanation {
+            start: CTerm::from_expr(&expl.start),
+            len:   steps.len(),
+            steps: Box::into_raw(steps) as *const CExplStep
+        }
+    }
+}
+
+// If `err` is not `ErrorCode::None`, then `msg` contains the error message and `expl` is empty.
+// Otherwise, `msg` is the empty string.
 #[repr(C)]
 pub struct EqsatResult {
-    expl: *const c_char,
-    graph: Option<Box<LeanEGraph>>,
-    report: CReport
+    expl: CExplanation,
+    msg: *const c_char,
+    graph: Option<Box<EGraphHandle>>,
+    report: CReport,
+    err: ErrorCode
+}
+
+impl EqsatResult {
+
+    fn from_error(err: Error) -> EqsatResult {
+        EqsatResult { 
+            expl: CExplanation::from_expl(None), 
+            msg: string_to_c_str(err.to_string()), 
+            graph: None, 
+            report: CReport::none(), 
+            err: err.code() 
+        }
+    }
+}
+
+// The result of FFI functions which return a string. If `err` is not `ErrorCode::None`, then `str` 
+// contains the error message.
+#[repr(C)]
+pub struct StrResult {
+    str: *const c_char,
+    err: ErrorCode
+}
+
+impl StrResult {
+
+    fn from_res(res: Res<String>) -> StrResult {
+        match res {
+            Ok(str)  => StrResult { str: string_to_c_str(str), err: ErrorCode::None },
+            Err(err) => StrResult { str: string_to_c_str(err.to_string()), err: err.code() }
+        }
+    }
+}
+
+// The result of FFI functions which return an explanation. If `err` is not `ErrorCode::None`, then 
+// `msg` contains the error message and `expl` is empty. Otherwise, `msg` is the empty string.
+#[repr(C)]
+pub struct ExplResult {
+    expl: CExplanation,
+    msg:  *const c_char,
+    err:  ErrorCode
+}
+
+impl ExplResult {
+
+    fn from_res(res: Res<Option<FlatExplanation<LeanExpr>>>) -> ExplResult {
+        match res {
+            Ok(expl) => ExplResult { expl: CExplanation::from_expl(expl), msg: string_to_c_str("".to_string()), err: ErrorCode::None },
+            Err(err) => ExplResult { expl: CExplanation::from_expl(None), msg: string_to_c_str(err.to_string()), err: err.code() }
+        }
+    }
 }
 
 #[no_mangle]
@@ -200,58 +362,299 @@ pub extern "C" fn slotted_explain_congr(
     cfg: Config,
     viz_path_ptr: *const c_char
 ) -> EqsatResult {
-    let init   = c_str_to_string(init_str_ptr);
-    let goal   = c_str_to_string(goal_str_ptr);
-    let guides = guides.to_vec();
-    let facts  = facts.to_vec();
+    let res = catch_panic(|| {
+        let init         = c_str_to_string(init_str_ptr);
+        let goal         = c_str_to_string(goal_str_ptr);
+        let guides       = guides.to_vec();
+        let facts        = facts.to_vec();
+        let rw_templates = rws.to_templates()?;
+
+        let raw_viz_path = c_str_to_string(viz_path_ptr);
+        let viz_path     = if raw_viz_path.is_empty() { None } else { Some(raw_viz_path) };
+
+        explain_congr(init, goal, rw_templates, facts, guides, cfg, viz_path)
+    });
+
+    match res {
+        Ok((expl, egraph, report)) => EqsatResult {
+            expl: CExplanation::from_expl(expl),
+            msg: string_to_c_str("".to_string()),
+            graph: Some(Box::new(Mutex::new(egraph))),
+            report: CReport::from_report(report),
+            err: ErrorCode::None
+        },
+        Err(err) => EqsatResult::from_error(err)
+    }
+}
+
+/// If querying panics, the e-graph is poisoned.
+///
+/// # Safety
+/// `egraph` must have been returned by `slotted_explain_congr` and not have been freed yet. The
+/// strings must be nul-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_query_equiv(
+    egraph: *const EGraphHandle,
+    init_str_ptr: *const c_char, 
+    goal_str_ptr: *const c_char
+) -> ExplResult {
+    ExplResult::from_res(catch_panic(|| {
+        let init = c_str_to_string(init_str_ptr);
+        let goal = c_str_to_string(goal_str_ptr);
+        query_equiv(&mut lock_handle(egraph), init, goal, /*optimize:*/false)
+    }))
+}
 
-    // Note: The `into_raw`s below are important, as otherwise Rust deallocates the string.
-    // TODO: I think this is a memory leak right now.
+/// # Safety
+/// `expl` must have been returned as part of the result of any of the functions in this file, and 
+/// must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_free_explanation(expl: CExplanation) {
+    catch_panic_in_free(|| {
+        expl.start.free();
+        if expl.steps.is_null() { return }
+
+        let steps = Box::from_raw(std::ptr::slice_from_raw_parts_mut(expl.steps as *mut CExplStep, expl.len));
+        for step in steps.into_vec() {
+            slotted_free_string(step.rule as *mut c_char);
+            step.dst.free();
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(step.pos as *mut usize, step.pos_len)));
+        }
+    })
+}
 
-    let rw_templates = rws.to_templates();
-    if let Err(rws_err) = rw_templates { 
-        return EqsatResult { expl: string_to_c_str(rws_err.to_string()), graph: None, report: CReport::none() }
+/// # Safety
+/// `egraph` must be null or have been returned by `slotted_explain_congr`, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_free_egraph(egraph: *mut EGraphHandle) {
+    catch_panic_in_free(|| if !egraph.is_null() { drop(Box::from_raw(egraph)); })
+}
+
+/// Frees a string returned by any of the functions above (including `CReport::rw_stats`).
+///
+/// # Safety
+/// `str` must be null or have been returned by one of these functions, and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_free_string(str: *mut c_char) {
+    catch_panic_in_free(|| if !str.is_null() { drop(CString::from_raw(str)); })
+}
+
+// E-graphs and sessions are handed to Lean, which may use them from multiple threads.
+type EGraphHandle = Mutex<LeanEGraph>;
+type SessionHandle = Mutex<Session>;
+
+// Panics while a handle is locked poison it, so that later calls fail with `ErrorCode::Panic` 
+// instead of using an e-graph which might be in an inconsistent state.
+unsafe fn lock_handle<'a, T>(handle: *const Mutex<T>) -> MutexGuard<'a, T> {
+    let handle = handle.as_ref().expect("handle is null");
+    handle.lock().expect("handle was poisoned by an earlier panic")
+}
+
+// If `err` is not `ErrorCode::None`, then `session` is absent and `msg` contains the error message. 
+// Otherwise, `msg` is the empty string.
+#[repr(C)]
+pub struct SessionResult {
+    msg: *const c_char,
+    session: Option<Box<SessionHandle>>,
+    err: ErrorCode
+}
+
+// Creates a session whose rewrites and facts are parsed once, and can then be used for multiple 
+// goals. The session has to be freed with `slotted_free_session`.
+#[no_mangle]
+pub extern "C" fn slotted_session_new(rws: CRewritesArray, facts: CFactsArray, cfg: Config) -> SessionResult {
+    let res = catch_panic(|| {
+        let facts = facts.to_vec();
+        Session::new(rws.to_templates()?, facts, cfg)
+    });
+    match res {
+        Ok(session) => SessionResult { msg: string_to_c_str("".to_string()), session: Some(Box::new(Mutex::new(session))), err: ErrorCode::None },
+        Err(err)    => SessionResult { msg: string_to_c_str(err.to_string()), session: None, err: err.code() }
     }
-    let rw_templates = rw_templates.unwrap();
+}
 
-    let viz_path_c_str = unsafe { CStr::from_ptr(viz_path_ptr) };
-    let raw_viz_path = String::from_utf8_lossy(viz_path_c_str.to_bytes()).to_string();
-    let viz_path = if raw_viz_path.is_empty() { None } else { Some(raw_viz_path) };
+// If `err` is not `ErrorCode::None`, then `msg` contains the error message. Otherwise, `msg` is the
+// empty string and `idx` is the index of the new goal, which can be passed to `slotted_session_explain_goal`.
+#[repr(C)]
+pub struct GoalResult {
+    idx: usize,
+    msg: *const c_char,
+    err: ErrorCode
+}
 
-    let res = explain_congr(init, goal, rw_templates, facts, guides, cfg, viz_path);
-    if let Err(res_err) = res {
-        return EqsatResult { expl: string_to_c_str(res_err.to_string()), graph: None, report: CReport::none() }
+/// # Safety
+/// `session` must have been returned by `slotted_session_new` and not have been freed yet. The
+/// strings must be nul-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_session_add_goal(
+    session: *const SessionHandle,
+    init_str_ptr: *const c_char, 
+    goal_str_ptr: *const c_char
+) -> GoalResult {
+    let res = catch_panic(|| {
+        let init = c_str_to_string(init_str_ptr);
+        let goal = c_str_to_string(goal_str_ptr);
+        lock_handle(session).add_goal(init, goal)
+    });
+    match res {
+        Ok(idx)  => GoalResult { idx, msg: string_to_c_str("".to_string()), err: ErrorCode::None },
+        Err(err) => GoalResult { idx: 0, msg: string_to_c_str(err.to_string()), err: err.code() }
     }
-    let (expl, egraph, report) = res.unwrap();
+}
+
+/// On success, the resulting string is empty.
+///
+/// # Safety
+/// Cf. `slotted_session_add_goal`.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_session_add_guide(session: *const SessionHandle, guide_str_ptr: *const c_char) -> StrResult {
+    StrResult::from_res(catch_panic(|| {
+        lock_handle(session).add_guide(c_str_to_string(guide_str_ptr)).map(|_| "".to_string())
+    }))
+}
+
+// If `err` is not `ErrorCode::None`, then `msg` contains the error message and `report` was created
+// by `CReport::none`. Otherwise, `msg` is the empty string.
+#[repr(C)]
+pub struct ReportResult {
+    report: CReport,
+    msg:    *const c_char,
+    err:    ErrorCode
+}
 
-    return EqsatResult {
-        expl: string_to_c_str(expl),
-        graph: Some(Box::new(egraph)),
-        report: CReport::from_report(report) 
+/// If saturation panics, the session is poisoned.
+///
+/// # Safety
+/// Cf. `slotted_session_add_goal`.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_session_saturate(session: *const SessionHandle) -> ReportResult {
+    let res = catch_panic(|| Ok(lock_handle(session).saturate()));
+    match res {
+        Ok(report) => ReportResult { report: CReport::from_report(report), msg: string_to_c_str("".to_string()), err: ErrorCode::None },
+        Err(err)   => ReportResult { report: CReport::none(), msg: string_to_c_str(err.to_string()), err: err.code() }
     }
 }
 
+/// Returns the explanation of the goal with index `idx`, which is empty if the goal has not been
+/// proven (yet).
+///
+/// # Safety
+/// Cf. `slotted_session_add_goal`.
 #[no_mangle]
-pub unsafe extern "C" fn slotted_query_equiv(
-    egraph: *mut LeanEGraph,
+pub unsafe extern "C" fn slotted_session_explain_goal(session: *const SessionHandle, idx: usize) -> ExplResult {
+    ExplResult::from_res(catch_panic(|| lock_handle(session).explain_goal(idx)))
+}
+
+/// Like `slotted_query_equiv`, but explanations are optimized according to the session's config.
+///
+/// # Safety
+/// Cf. `slotted_session_add_goal`.
+#[no_mangle]
+pub unsafe extern "C" fn slotted_session_query_equiv(
+    session: *const SessionHandle,
     init_str_ptr: *const c_char, 
     goal_str_ptr: *const c_char
-) -> *const c_char {
-    let egraph = egraph.as_mut().unwrap();
-    let init = RecExpr::parse(&c_str_to_string(init_str_ptr)).unwrap();
-    let goal = RecExpr::parse(&c_str_to_string(goal_str_ptr)).unwrap();
-    let init_id = egraph.add_expr(init.clone());
-    let goal_id = egraph.add_expr(goal.clone());
-
-    if egraph.eq(&init_id, &goal_id) {
-        let expl = egraph.explain_equivalence(init, goal).to_flat_string(&egraph);
-        string_to_c_str(expl)
-    } else {
-        string_to_c_str("".to_string())
-    }
+) -> ExplResult {
+    ExplResult::from_res(catch_panic(|| {
+        let init = c_str_to_string(init_str_ptr);
+        let goal = c_str_to_string(goal_str_ptr);
+        lock_handle(session).query_equiv(init, goal)
+    }))
 }
 
+/// # Safety
+/// `session` must be null or have been returned by `slotted_session_new`, and must not be used
+/// afterwards.
 #[no_mangle]
-pub unsafe extern "C" fn slotted_free_egraph(egraph: *mut LeanEGraph) {
-    if !egraph.is_null() { drop(Box::from_raw(egraph)); }
-}
\ No newline at end of file
+pub unsafe extern "C" fn slotted_free_session(session: *mut SessionHandle) {
+    catch_panic_in_free(|| if !session.is_null() { drop(Box::from_raw(session)); })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn nodes(term: &CTerm) -> Vec<(String, String, usize)> {
+        let nodes = std::slice::from_raw_parts(term.nodes, term.len);
+        nodes.iter().map(|node| (c_str_to_string(node.op), c_str_to_string(node.slot), node.arity)).collect()
+    }
+
+    #[test]
+    fn terms_are_marshalled_in_pre_order() {
+        let expr = RecExpr::parse("(λ $x (sort 0) (app (const \"f\" 1) (bvar $x)))").unwrap();
+        let term = CTerm::from_expr(&expr);
+        let expected = vec![
+            ("λ", "x", 2), ("sort", "", 1), ("0", "", 0), ("app", "", 2), 
+            ("const", "", 2), ("\"f\"", "", 0), ("1", "", 0), ("bvar", "x", 0)
+        ];
+        let expected: Vec<_> = expected.into_iter().map(|(op, slot, arity)| (op.to_string(), slot.to_string(), arity)).collect();
+        unsafe {
+            assert_eq!(nodes(&term), expected);
+            term.free();
+        }
+    }
+
+    fn poison<T>(handle: &Mutex<T>) {
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = handle.lock().unwrap();
+            panic!("poison");
+        }));
+        assert!(handle.is_poisoned());
+    }
+
+    unsafe fn take_err(msg: *const c_char, err: ErrorCode) -> (ErrorCode, String) {
+        let res = (err, c_str_to_string(msg));
+        slotted_free_string(msg as *mut c_char);
+        res
+    }
+
+    #[test]
+    fn poisoned_sessions_report_panics() {
+        let session = Session::new(vec![], vec![], Config::default()).unwrap();
+        let session = Box::into_raw(Box::new(Mutex::new(session)));
+        let init = CString::new("(sort 0)").unwrap();
+        let goal = CString::new("(sort 1)").unwrap();
+        unsafe {
+            let res = slotted_session_add_goal(session, init.as_ptr(), goal.as_ptr());
+            assert_eq!(take_err(res.msg, res.err), (ErrorCode::None, "".to_string()));
+
+            poison(&*session);
+
+            let res = slotted_session_add_goal(session, init.as_ptr(), goal.as_ptr());
+            let (err, msg) = take_err(res.msg, res.err);
+            assert_eq!(err, ErrorCode::Panic);
+            assert!(msg.contains("poisoned"), "{msg}");
+
+            let res = slotted_session_saturate(session);
+            assert!(res.report.rw_stats.is_null());
+            assert_eq!(take_err(res.msg, res.err).0, ErrorCode::Panic);
+
+            let res = slotted_session_explain_goal(session, 0);
+            assert!(res.expl.start.nodes.is_null());
+            assert_eq!(take_err(res.msg, res.err).0, ErrorCode::Panic);
+
+            slotted_free_session(session);
+        }
+    }
+
+    #[test]
+    fn poisoned_egraphs_report_panics() {
+        let egraph = Box::into_raw(Box::new(Mutex::new(LeanEGraph::new())));
+        let init = CString::new("(sort 0)").unwrap();
+        unsafe {
+            let res = slotted_query_equiv(egraph, init.as_ptr(), init.as_ptr());
+            assert_eq!(take_err(res.msg, res.err).0, ErrorCode::None);
+            slotted_free_explanation(res.expl);
+
+            poison(&*egraph);
+
+            let res = slotted_query_equiv(egraph, init.as_ptr(), init.as_ptr());
+            assert!(res.expl.start.nodes.is_null());
+            assert_eq!(take_err(res.msg, res.err).0, ErrorCode::Panic);
+
+            slotted_free_egraph(egraph);
+        }
+    }
+}
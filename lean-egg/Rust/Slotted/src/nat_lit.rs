@@ -1,49 +1,8 @@
 use slotted_egraphs::*;
 use crate::lean_expr::*;
 use crate::analysis::*;
-// use std::ops::*;
 
-/*
-struct Op {
-    lhs_nat_val: Var,
-    rhs_nat_val: Var,
-    op: fn(u64, u64) -> u64
-}
-
-impl Applier<LeanExpr, LeanAnalysis> for Op {
-
-    fn apply_one(&self, egraph: &mut LeanEGraph, _: Id, subst: &Subst, ast: Option<&PatternAst<LeanExpr>>, rule: Symbol) -> Vec<Id> {
-        // This applier is only used in a context where we know that `nat_val` is a `LeanExpr::Nat` and thus has a `nat_val`.
-        let lhs = egraph[subst[self.lhs_nat_val]].data.nat_val.unwrap();
-        let rhs = egraph[subst[self.rhs_nat_val]].data.nat_val.unwrap();
-        // The `ast` is present when explanations are enabled, which we always do.
-        let ast = ast.unwrap(); 
-        
-        let val = (self.op)(lhs, rhs);
-        let res = format!("(lit {})", val).parse().unwrap();
-        let (id, _) = egraph.union_instantiations(ast, &res, subst, rule);
-        vec![id]
-    }
-}
-
-// The supported internalizations can be found at:
-// https://github.com/leanprover/lean4/blob/1e74c6a348416677987cd71a59a451db0aef9e26/src/kernel/type_checker.cpp#L1138
-pub fn nat_lit_rws() -> Vec<LeanRewrite> {
-    let mut rws = vec![];
-    rws.append(&mut rewrite!("≡0";  "(lit 0)"                                      <=> "(const Nat.zero)"));
-    rws.push(       rewrite!("≡→S"; "(lit ?n)"                                      => { ToSucc { nat_val : "?n".parse().unwrap() }}));
-    rws.push(       rewrite!("≡S→"; "(app (const Nat.succ) (lit ?n))"               => { OfSucc { nat_val : "?n".parse().unwrap() }}));
-    rws.push(       rewrite!("≡+";  "(app (app (const Nat.add) (lit ?l)) (lit ?r))" => { Op { op: u64::add,            lhs_nat_val : "?l".parse().unwrap(), rhs_nat_val : "?r".parse().unwrap() }}));
-    rws.push(       rewrite!("≡-";  "(app (app (const Nat.sub) (lit ?l)) (lit ?r))" => { Op { op: u64::saturating_sub, lhs_nat_val : "?l".parse().unwrap(), rhs_nat_val : "?r".parse().unwrap() }}));
-    rws.push(       rewrite!("≡*";  "(app (app (const Nat.mul) (lit ?l)) (lit ?r))" => { Op { op: u64::mul,            lhs_nat_val : "?l".parse().unwrap(), rhs_nat_val : "?r".parse().unwrap() }}));
-    rws.push(       rewrite!("≡^";  "(app (app (const Nat.pow) (lit ?l)) (lit ?r))" => { Op { op: u64_pow,             lhs_nat_val : "?l".parse().unwrap(), rhs_nat_val : "?r".parse().unwrap() }}));
-    rws.push(       rewrite!("≡/";  "(app (app (const Nat.div) (lit ?l)) (lit ?r))" => { Op { op: u64_div,             lhs_nat_val : "?l".parse().unwrap(), rhs_nat_val : "?r".parse().unwrap() }}));
-    rws.push(       rewrite!("≡%";  "(app (app (const Nat.mod) (lit ?l)) (lit ?r))" => { Op { op: u64_mod,             lhs_nat_val : "?l".parse().unwrap(), rhs_nat_val : "?r".parse().unwrap() }}));
-    rws
-}
-*/
-
-fn to_succ_rw() -> LeanRewrite {
+fn to_succ_rw(shapes: bool) -> LeanRewrite {
     let lhs: Pattern<LeanExpr> = Pattern::parse("(lit ?n)").unwrap();
     let lhs_search = lhs.clone();
     let lhs_apply = lhs.clone();
@@ -51,13 +10,15 @@ fn to_succ_rw() -> LeanRewrite {
         searcher: Box::new(move |graph| { ematch_all(graph, &lhs_search) }),
         applier: Box::new(move |substs, graph| {
             for subst in substs {
-                let lhs = pattern_subst(graph, &lhs_apply, &subst);
-                let analysis: &LeanAnalysis = graph.analysis_data(lhs.id);
+                let analysis: &LeanAnalysis = graph.analysis_data(subst["n"].id);
                 
                 // This applier matches against "lit ?n", which means that `?n` might be a string.
                 if let Some(nat_val) = analysis.nat_val {
-                    if !(nat_val > 0) { return }
-                    let rhs = Pattern::parse(&format!("(app (const \"Nat.succ\") (lit {}))", nat_val - 1)).unwrap();
+                    if !(nat_val > 0) { continue }
+                    let rhs = 
+                        if shapes { format!("(app (◇ (→ * *) (const \"Nat.succ\")) (◇ * (lit {})))", nat_val - 1) } 
+                        else      { format!("(app (const \"Nat.succ\") (lit {}))",                   nat_val - 1) };
+                    let rhs = Pattern::parse(&rhs).unwrap();
                     graph.union_instantiations(&lhs_apply, &rhs, &subst, Some("≡→S".to_string()));
                 }
             }
@@ -65,19 +26,22 @@ fn to_succ_rw() -> LeanRewrite {
     }.into()
 }
 
-fn of_succ_rw() -> LeanRewrite {
-    let lhs: Pattern<LeanExpr> = Pattern::parse("(app (const \"Nat.succ\") (lit ?n))").unwrap();
+fn of_succ_rw(shapes: bool) -> LeanRewrite {
+    let lhs = 
+        if shapes { "(app (◇ (→ * *) (const \"Nat.succ\")) (◇ * (lit ?n)))" } 
+        else      { "(app (const \"Nat.succ\") (lit ?n))" };
+    let lhs: Pattern<LeanExpr> = Pattern::parse(lhs).unwrap();
     let lhs_search = lhs.clone();
     let lhs_apply = lhs.clone();
     RewriteT {
         searcher: Box::new(move |graph| { ematch_all(graph, &lhs_search) }),
         applier: Box::new(move |substs, graph| {
             for subst in substs {
-                let lhs = pattern_subst(graph, &lhs_apply, &subst);
-                let analysis: &LeanAnalysis = graph.analysis_data(lhs.id);
+                let analysis: &LeanAnalysis = graph.analysis_data(subst["n"].id);
                 
                 if let Some(nat_val) = analysis.nat_val {
-                    let rhs = Pattern::parse(&format!("(lit {})", nat_val + 1)).unwrap();
+                    let Some(val) = nat_val.checked_add(1) else { continue };
+                    let rhs = Pattern::parse(&format!("(lit {})", val)).unwrap();
                     graph.union_instantiations(&lhs_apply, &rhs, &subst, Some("≡S→".to_string()));
                 }
             }
@@ -85,27 +49,131 @@ fn of_succ_rw() -> LeanRewrite {
     }.into()
 }
 
+// Computes `Nat.{op_name} l r` for literals `l` and `r`.
+// If `op` returns `None`, the result is not representable as a `u64`, so no equality is added.
+fn op_rw(rule: &str, op_name: &str, op: fn(u64, u64) -> Option<u64>, shapes: bool) -> LeanRewrite {
+    let lhs = 
+        if shapes { format!("(app (◇ (→ * *) (app (◇ (→ * (→ * *)) (const \"Nat.{}\")) (◇ * (lit ?l)))) (◇ * (lit ?r)))", op_name) } 
+        else      { format!("(app (app (const \"Nat.{}\") (lit ?l)) (lit ?r))",                                             op_name) };
+    let lhs: Pattern<LeanExpr> = Pattern::parse(&lhs).unwrap();
+    let lhs_search = lhs.clone();
+    let lhs_apply = lhs.clone();
+    let rule = rule.to_string();
+    RewriteT {
+        searcher: Box::new(move |graph| { ematch_all(graph, &lhs_search) }),
+        applier: Box::new(move |substs, graph| {
+            for subst in substs {
+                // The literals might be strings, in which case there is no `nat_val`.
+                let lhs: &LeanAnalysis = graph.analysis_data(subst["l"].id);
+                let rhs: &LeanAnalysis = graph.analysis_data(subst["r"].id);
+                let (Some(lhs), Some(rhs)) = (lhs.nat_val, rhs.nat_val) else { continue };
+                let Some(val) = op(lhs, rhs) else { continue };
+
+                let rhs = Pattern::parse(&format!("(lit {})", val)).unwrap();
+                graph.union_instantiations(&lhs_apply, &rhs, &subst, Some(rule.clone()));
+            }
+        }),
+    }.into()
+}
+
 // The supported internalizations can be found at:
 // https://github.com/leanprover/lean4/blob/1e74c6a348416677987cd71a59a451db0aef9e26/src/kernel/type_checker.cpp#L1138
-pub fn nat_lit_rws() -> Vec<LeanRewrite> {
+pub fn nat_lit_rws(shapes: bool) -> Vec<LeanRewrite> {
     let mut rws = vec![];
     rws.push(Rewrite::new("≡0", "(lit 0)", "(const \"Nat.zero\")"));
     rws.push(Rewrite::new("≡0-rev", "(const \"Nat.zero\")", "(lit 0)"));
-    rws.push(to_succ_rw());
-    rws.push(of_succ_rw());
+    rws.push(to_succ_rw(shapes));
+    rws.push(of_succ_rw(shapes));
+    rws.push(op_rw("≡+", "add", u64::checked_add, shapes));
+    rws.push(op_rw("≡-", "sub", u64_sub, shapes));
+    rws.push(op_rw("≡*", "mul", u64::checked_mul, shapes));
+    rws.push(op_rw("≡^", "pow", u64_pow, shapes));
+    rws.push(op_rw("≡/", "div", u64_div, shapes));
+    rws.push(op_rw("≡%", "mod", u64_mod, shapes));
     rws
 }
 
-/*
-fn u64_pow(lhs: u64, rhs: u64) -> u64 {
-    lhs.pow(u32::try_from(rhs).unwrap())
+fn u64_sub(lhs: u64, rhs: u64) -> Option<u64> {
+    Some(lhs.saturating_sub(rhs))
+}
+
+fn u64_pow(lhs: u64, rhs: u64) -> Option<u64> {
+    lhs.checked_pow(u32::try_from(rhs).ok()?)
+}
+
+fn u64_div(lhs: u64, rhs: u64) -> Option<u64> {
+    Some(lhs.checked_div(rhs).unwrap_or(0))
 }
 
-fn u64_div(lhs: u64, rhs: u64) -> u64 {
-    lhs.checked_div(rhs).unwrap_or(0)
+fn u64_mod(lhs: u64, rhs: u64) -> Option<u64> {
+    Some(if rhs == 0 { lhs } else { lhs % rhs })
 }
 
-fn u64_mod(lhs: u64, rhs: u64) -> u64 {
-    if rhs == 0 { lhs } else { lhs % rhs }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Adds `Nat.{op_name} l r` in the encoding of `op_rw` and applies the nat literal rewrites once.
+    fn eval(op_name: &str, l: u64, r: u64, shapes: bool) -> (LeanEGraph, AppliedId) {
+        let expr = 
+            if shapes { format!("(app (◇ (→ * *) (app (◇ (→ * (→ * *)) (const \"Nat.{op_name}\")) (◇ * (lit {l})))) (◇ * (lit {r})))") } 
+            else      { format!("(app (app (const \"Nat.{op_name}\") (lit {l})) (lit {r}))") };
+        let mut eg = LeanEGraph::new();
+        let i = eg.add_expr(RecExpr::parse(&expr).unwrap());
+        apply_rewrites(&mut eg, &nat_lit_rws(shapes));
+        (eg, i)
+    }
+
+    // The expected values are the ones computed by the egg backend.
+    fn assert_evaluates(op_name: &str, l: u64, r: u64, val: u64) {
+        for shapes in [false, true] {
+            let (mut eg, i) = eval(op_name, l, r, shapes);
+            let lit = eg.add_expr(RecExpr::parse(&format!("(lit {val})")).unwrap());
+            assert!(eg.eq(&i, &lit), "Nat.{op_name} {l} {r} ≠ {val} (shapes: {shapes})");
+        }
+    }
+
+    fn assert_not_evaluated(op_name: &str, l: u64, r: u64) {
+        for shapes in [false, true] {
+            let (eg, i) = eval(op_name, l, r, shapes);
+            let has_lit = eg.enodes(i.id).iter().any(|n| matches!(n, LeanExpr::Lit(_)));
+            assert!(!has_lit, "Nat.{op_name} {l} {r} was evaluated (shapes: {shapes})");
+        }
+    }
+
+    #[test]
+    fn evaluates_ops() {
+        assert_evaluates("add", 2, 3, 5);
+        assert_evaluates("sub", 7, 5, 2);
+        assert_evaluates("mul", 2, 3, 6);
+        assert_evaluates("pow", 2, 3, 8);
+        assert_evaluates("div", 7, 2, 3);
+        assert_evaluates("mod", 7, 2, 1);
+    }
+
+    #[test]
+    fn sub_truncates() {
+        assert_evaluates("sub", 5, 7, 0);
+        assert_evaluates("sub", 0, u64::MAX, 0);
+    }
+
+    #[test]
+    fn div_and_mod_by_zero() {
+        assert_evaluates("div", 7, 0, 0);
+        assert_evaluates("mod", 7, 0, 7);
+        assert_evaluates("div", 0, 0, 0);
+        assert_evaluates("mod", 0, 0, 0);
+    }
+
+    // egg overflows on these, while the slotted backend leaves them unevaluated.
+    #[test]
+    fn overflow_is_not_evaluated() {
+        assert_not_evaluated("add", u64::MAX, 1);
+        assert_not_evaluated("mul", u64::MAX, 2);
+        assert_not_evaluated("pow", 2, 64);
+        assert_not_evaluated("pow", 2, u64::from(u32::MAX) + 1);
+        assert_evaluates("add", u64::MAX - 1, 1, u64::MAX);
+        assert_evaluates("pow", 2, 63, 1 << 63);
+    }
 }
-     */
\ No newline at end of file
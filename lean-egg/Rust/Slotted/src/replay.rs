@@ -0,0 +1,38 @@
+use lean_egg_request::*;
+use crate::basic::explain_congr;
+use crate::rewrite::*;
+use crate::*;
+
+// Runs a request recorded by the `egg.exportPath` option without Lean. This is used by the parity
+// harness in `Rust/Parity` to compare the slotted and egg backends.
+pub fn replay(enc: &Encoding, cfg: Config) -> Result<Outcome, String> {
+    catch_panic(|| {
+        let mut rw_templates = vec![];
+        for rw in &enc.rws {
+            rw_templates.extend(parse_templates(&rw.name, &rw.lhs, &rw.rhs, &rw.conds, &rw.dirs)?);
+        }
+        let (expl, _, report) = explain_congr(enc.init.clone(), enc.goal.clone(), rw_templates, enc.facts.clone(), enc.guides.clone(), cfg, None)?;
+        let expl = expl.map(|expl| {
+            let terms: Vec<String> = std::iter::once(&expl.start).chain(expl.steps.iter().map(|step| &step.dst)).map(|e| e.to_string()).collect();
+            terms.join("\n")
+        }).unwrap_or_default();
+        
+        let stop_reason = match report.stop_reason {
+            StopReason::Saturated      => "saturated".to_string(),
+            StopReason::IterationLimit => "reached iteration limit".to_string(),
+            StopReason::TimeLimit      => "reached time limit".to_string(),
+            StopReason::MemoryLimit    => "reached memory limit".to_string(),
+            StopReason::GoalsReached   => "proved goal".to_string(),
+            StopReason::Other(msg)     => msg,
+        };
+        Ok(Outcome {
+            expl,
+            iterations: report.iterations,
+            stop_reason,
+            nodes:      report.egraph_nodes,
+            classes:    report.egraph_classes,
+            time:       report.total_time
+        })
+    })
+    .map_err(|err| err.to_string())
+}
@@ -5,19 +5,49 @@ pub enum Error {
     Guide(String),
     Fact(String),
     Rewrite(String),
+    Panic(String),
+}
+
+// The kinds of errors reported across the FFI. `None` indicates that no error occurred.
+// IMPORTANT: The C interface depends on the order of these variants.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    None,
+    Init,
+    Goal,
+    Guide,
+    Fact,
+    Rewrite,
+    Panic,
+}
+
+impl Error {
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Init(_)    => ErrorCode::Init,
+            Error::Goal(_)    => ErrorCode::Goal,
+            Error::Guide(_)   => ErrorCode::Guide,
+            Error::Fact(_)    => ErrorCode::Fact,
+            Error::Rewrite(_) => ErrorCode::Rewrite,
+            Error::Panic(_)   => ErrorCode::Panic,
+        }
+    }
 }
 
 impl ToString for Error {
 
     fn to_string(&self) -> String {
         match self {
-            Error::Init(s)    => format!("⚡️ {s}"),
-            Error::Goal(s)    => format!("⚡️ {s}"),
-            Error::Guide(s)   => format!("⚡️ {s}"),
-            Error::Fact(s)    => format!("⚡️ {s}"),
-            Error::Rewrite(s) => format!("⚡️ {s}"),
+            Error::Init(s)    => s.clone(),
+            Error::Goal(s)    => s.clone(),
+            Error::Guide(s)   => s.clone(),
+            Error::Fact(s)    => s.clone(),
+            Error::Rewrite(s) => s.clone(),
+            Error::Panic(s)   => format!("Panicked: {s}"),
         }
     }
 }
 
-pub type Res<T> = Result<T, Error>;
\ No newline at end of file
+pub type Res<T> = Result<T, Error>;
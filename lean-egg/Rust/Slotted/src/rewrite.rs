@@ -1,9 +1,9 @@
-use std::collections::HashMap;
 use std::collections::HashSet;
 use slotted_egraphs::*;
 use crate::result::*;
 use crate::lean_expr::*;
 use crate::analysis::*;
+use crate::RewriteDirections;
 
 pub struct RewriteTemplate {
     pub name:  String,
@@ -12,6 +12,31 @@ pub struct RewriteTemplate {
     pub conds: Vec<Pattern<LeanExpr>>
 }
 
+// Parses a rewrite into one template per direction in `dirs`.
+pub fn parse_templates(name: &str, lhs: &str, rhs: &str, conds: &[String], dirs: &RewriteDirections) -> Res<Vec<RewriteTemplate>> {
+    let parse = |what: &str, s: &str| Pattern::parse(s).map_err(|err| {
+        Error::Rewrite(format!("Failed to parse {what} of rewrite '{name}': {err}\n\n  {s}"))
+    });
+    let lhs   = parse("lhs", lhs)?;
+    let rhs   = parse("rhs", rhs)?;
+    let conds = conds.iter().map(|cond| parse("condition", cond)).collect::<Res<Vec<_>>>()?;
+
+    let mut res: Vec<RewriteTemplate> = vec![];
+    if *dirs == RewriteDirections::Forward || *dirs == RewriteDirections::Both {
+        res.push(RewriteTemplate { name: name.to_string(), lhs: lhs.clone(), rhs: rhs.clone(), conds: conds.clone() })
+    }
+    if *dirs == RewriteDirections::Backward || *dirs == RewriteDirections::Both {
+        // It is important that we use the "-rev" suffix for reverse rules here, as this is also
+        // what's used for adding the reverse rule when using egg's `rewrite!(_; _ <=> _)` macro,
+        // and by `Rewrite::new_bidir`.
+        // If we choose another naming scheme, egg may complain about duplicate rules when 
+        // `rw.dir == RewriteDirection::Both`. This is the case, for example, for the rewrite
+        // `?a + ?b = ?b + ?a`.
+        res.push(RewriteTemplate { name: reverse_rule_name(name), lhs: rhs, rhs: lhs, conds })
+    }
+    Ok(res)
+}
+
 fn slots_for_node(e: &LeanExpr) -> HashSet<Slot> {
     match e {
         LeanExpr::BVar(s) | LeanExpr::Lam(s, _, _) | LeanExpr::Forall(s, _, _) => HashSet::from([*s]), 
@@ -43,16 +68,23 @@ fn subst_is_valid(subst: &Subst, illegal_slots: &HashSet<Slot>) -> bool {
     true
 }
 
+// A match is valid if it does not map a pattern variable to an e-class which uses any of the slots
+// bound by the rewrite. This is the slotted equivalent of Condition (1) of egg's `match_is_valid`.
+// Condition (2) holds trivially, as slots are referred to by name instead of by de Bruijn index.
+// For the same reason, captured bound variables never need to be shifted. Thus, `shift_captured_bvars`
+// only implies `block_invalid_matches`, as it does for egg.
 pub fn templates_to_rewrites(
     templates: Vec<RewriteTemplate>, 
-    facts: HashMap<AppliedId, String>, 
+    block_invalid_matches: bool, 
+    shift_captured_bvars: bool, 
     allow_unsat_conditions: bool
 ) -> Res<Vec<LeanRewrite>> {
+    let block_invalid_matches = block_invalid_matches || shift_captured_bvars;
+
     let mut result: Vec<LeanRewrite> = vec![];
     for template in templates {
         let lhs_search = template.lhs.clone();
         let lhs_apply = template.lhs.clone();
-        let facts = facts.clone();
         
         let mut illegal_slots = private_slots(&template.lhs);
         illegal_slots.extend(&private_slots(&template.rhs));
@@ -61,7 +93,7 @@ pub fn templates_to_rewrites(
             searcher: Box::new(move |graph| { ematch_all(graph, &lhs_search) }),
             applier: Box::new(move |substs, graph| {
                 for subst in substs {
-                    if !subst_is_valid(&subst, &illegal_slots) { continue; }
+                    if block_invalid_matches && !subst_is_valid(&subst, &illegal_slots) { continue; }
 
                     let lhs = pattern_subst(graph, &lhs_apply, &subst);
                     let analysis: &LeanAnalysis = graph.analysis_data(lhs.id);
@@ -74,14 +106,10 @@ pub fn templates_to_rewrites(
                     for cond in template.conds.clone() {
                         let id = pattern_subst(graph, &cond, &subst);
                         
-                        // Note: If we don't find a fact matching `id`, this might just be because 
-                        //       the fact id isn't canonical. Thus, in the `else if` branch we also 
-                        //       check whether there exists a fact id whose canonicalization matches 
-                        //       `id`.
-                        if let Some(fact_name) = facts.get(&id) {
-                            rule = rule.as_str().to_string(); rule.push_str(&fact_name);
-                        } else if let Some((_, fact_name)) = facts.iter().find(|(f_id, _)| graph.eq(f_id, &id)) { 
-                            rule = rule.as_str().to_string(); rule.push_str(&fact_name);
+                        // Note: The facts are registered in the e-graph (cf. `Session::new`), which keeps
+                        //       them canonical across unions.
+                        if let Some(fact_name) = graph.fact(&id) {
+                            rule = rule.as_str().to_string(); rule.push_str(fact_name);
                         } else if allow_unsat_conditions {
                             rule = rule.as_str().to_string(); rule.push_str("!?");
                         } else {
@@ -96,4 +124,44 @@ pub fn templates_to_rewrites(
         result.push(rw.into());
     }
     Ok(result)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrites(lhs: &str, rhs: &str, block_invalid_matches: bool, shift_captured_bvars: bool) -> Vec<LeanRewrite> {
+        let templates = parse_templates("rw", lhs, rhs, &[], &RewriteDirections::Forward).unwrap();
+        templates_to_rewrites(templates, block_invalid_matches, shift_captured_bvars, false).unwrap()
+    }
+
+    // Rewrites `λ x, b` to `b`, which captures `x` if `b` mentions it.
+    #[test]
+    fn captured_slots_are_blocked() {
+        let capturing = RecExpr::parse("(λ $0 (const \"Nat\") (bvar $0))").unwrap();
+        let constant = RecExpr::parse("(λ $0 (const \"Nat\") (const \"a\"))").unwrap();
+        for (block, shift, blocked) in [(true, false, true), (false, true, true), (false, false, false)] {
+            let mut eg = LeanEGraph::new();
+            let i = eg.add_expr(capturing.clone());
+            let j = eg.add_expr(constant.clone());
+            let a = eg.add_expr(RecExpr::parse("(const \"a\")").unwrap());
+            apply_rewrites(&mut eg, &rewrites("(λ $1 ?t ?b)", "?b", block, shift));
+
+            assert_eq!(eg.enodes(i.id).len() == 1, blocked, "block: {block}, shift: {shift}");
+            assert!(eg.eq(&j, &a));
+        }
+    }
+
+    #[test]
+    fn shaped_nat_literals_are_rewritten() {
+        let mut eg = LeanEGraph::new();
+        let start = eg.add_expr(RecExpr::parse("(app (◇ (→ * *) (const \"f\")) (◇ * (lit 3)))").unwrap());
+        let goal = eg.add_expr(RecExpr::parse("(app (◇ (→ * *) (const \"g\")) (◇ * (lit 3)))").unwrap());
+        let string = eg.add_expr(RecExpr::parse("(app (◇ (→ * *) (const \"f\")) (◇ \"*\" (lit 3)))").unwrap());
+        let rws = rewrites("(app (◇ (→ * *) (const \"f\")) (◇ * (lit ?n)))", "(app (◇ (→ * *) (const \"g\")) (◇ * (lit ?n)))", true, false);
+        apply_rewrites(&mut eg, &rws);
+
+        assert!(eg.eq(&start, &goal));
+        // The string literal `"*"` is not a shape.
+        assert!(!eg.eq(&string, &goal));
+    }
+}
@@ -113,6 +113,15 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         let all_classes = self.classes.keys().copied().collect::<HashSet<_>>();
         let all: HashSet<Id> = &(&all_keys | &all_values) | &all_classes;
         for i in all {
+            // pruned ids are neither alive nor dead.
+            if self.is_pruned(i) {
+                if let Some(c) = self.classes.get(&i) {
+                    assert!(c.nodes.is_empty());
+                    assert!(c.usages.is_empty());
+                }
+                continue;
+            }
+
             // if they point to themselves, they should do it using the identity.
             if self.is_alive(i) {
                 assert_eq!(self.unionfind_get(i), self.mk_sem_identity_applied_id(i));
@@ -130,7 +139,8 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
         }
 
         // Check that the Unionfind has valid AppliedIds.
-        for (_, app_id) in self.unionfind_iter() {
+        for (i, app_id) in self.unionfind_iter() {
+            if self.is_pruned(i) { continue; }
             check_internal_applied_id::<L, N>(self, &app_id);
         }
 
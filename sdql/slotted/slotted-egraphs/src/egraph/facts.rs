@@ -0,0 +1,46 @@
+use crate::*;
+
+impl<L: Language, N: Analysis<L>> EGraph<L, N> {
+    /// Registers the e-class `i` as a fact called `name`, e.g. a hypothesis that can discharge the conditions of conditional rewrites.
+    ///
+    /// Facts are kept up to date with later unions, so [EGraph::fact] also finds them using any [AppliedId] equal to `i`.
+    /// Returns false, if an equal fact was registered already. In that case, the old name is kept.
+    pub fn add_fact(&mut self, i: &AppliedId, name: String) -> bool {
+        if self.fact(i).is_some() { return false; }
+
+        let i = self.find_applied_id(i);
+        self.facts.entry(i.id).or_default().push((i, name));
+        true
+    }
+
+    /// Returns the name of the fact equal to `i`, if there is one.
+    ///
+    /// Only facts whose slots are mapped to the same slots as in `i` are found, see [EGraph::eq].
+    /// Like [EGraph::eq], this takes unions into account immediately, but congruences only after [EGraph::rebuild].
+    pub fn fact(&self, i: &AppliedId) -> Option<&str> {
+        let id = self.find_id(i.id);
+        let facts = self.facts.get(&id)?;
+        facts.iter().find(|(f, _)| self.eq(f, i)).map(|(_, name)| name.as_str())
+    }
+
+    /// Returns all facts, keyed by their names.
+    pub fn facts(&self) -> Vec<(String, AppliedId)> {
+        self.facts.values()
+                  .flatten()
+                  .map(|(f, name)| (name.clone(), self.find_applied_id(f)))
+                  .collect()
+    }
+
+    // Moves the facts of the e-class `from` to the e-class it was merged into.
+    pub(in crate::egraph) fn move_facts(&mut self, from: Id) {
+        let Some(facts) = self.facts.remove(&from) else { return };
+
+        for (f, name) in facts {
+            let f = self.find_applied_id(&f);
+
+            // Unions can make two facts equal. Then, only the older one is kept.
+            if self.fact(&f).is_some() { continue; }
+            self.facts.entry(f.id).or_default().push((f, name));
+        }
+    }
+}
@@ -1,24 +1,37 @@
 use crate::*;
 
 impl<L: Language, N: Analysis<L>> EGraph<L, N> {
-    fn unionfind_get_impl(&self, i: Id, map: &mut [ProvenAppliedId]) -> ProvenAppliedId {
-        let entry = &mut map[i.0];
+    // Only reads from the unionfind, so that it can be called concurrently.
+    // The path compressions that should be written back are collected in `updates`.
+    fn unionfind_get_impl(&self, i: Id, map: &[ProvenAppliedId], updates: &mut Vec<(Id, ProvenAppliedId)>) -> ProvenAppliedId {
+        let entry = &map[i.0];
 
         if entry.elem.id == i {
             return entry.clone();
         }
 
-        let entry = entry.clone();
-
         // entry.0.m :: slots(entry.0.id) -> slots(i)
         // entry_to_leader.0.m :: slots(leader) -> slots(entry.0.id)
-        let entry_to_leader = self.unionfind_get_impl(entry.elem.id, map);
-        let new = self.chain_pai(&entry, &entry_to_leader);
+        let entry_to_leader = self.unionfind_get_impl(entry.elem.id, map, updates);
+        let new = self.chain_pai(entry, &entry_to_leader);
 
-        map[i.0] = new.clone();
+        // Only write back if something changed, so that repeated lookups don't need the write lock.
+        if new.elem != entry.elem {
+            updates.push((i, new.clone()));
+        }
         new
     }
 
+    fn unionfind_write_updates(&self, updates: Vec<(Id, ProvenAppliedId)>) {
+        if updates.is_empty() { return; }
+
+        // Concurrent readers might compute the same compressions, which is harmless.
+        let mut map = self.unionfind.write().unwrap();
+        for (i, pai) in updates {
+            map[i.0] = pai;
+        }
+    }
+
     pub(crate) fn unionfind_set(&self, i: Id, pai: ProvenAppliedId) {
         #[cfg(feature = "explanations")]
         if CHECKS {
@@ -27,7 +40,7 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
             assert_eq!(pai.elem.id, pai.proof.r.id);
         }
 
-        let mut lock = self.unionfind.borrow_mut();
+        let mut lock = self.unionfind.write().unwrap();
         if lock.len() == i.0 {
             lock.push(pai);
         } else {
@@ -36,34 +49,43 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
     }
 
     pub(crate) fn proven_unionfind_get(&self, i: Id) -> ProvenAppliedId {
-        let mut map = self.unionfind.borrow_mut();
-        self.unionfind_get_impl(i, &mut *map)
+        let mut updates = Vec::new();
+        let out = {
+            let map = self.unionfind.read().unwrap();
+            self.unionfind_get_impl(i, &map, &mut updates)
+        };
+        self.unionfind_write_updates(updates);
+        out
     }
 
     pub(crate) fn unionfind_get(&self, i: Id) -> AppliedId {
         self.proven_unionfind_get(i).elem
     }
 
-    /// Returns whether an id is still alive, or whether it was merged into another class.
+    /// Returns whether an id is still alive, or whether it was merged into another class or pruned.
     pub fn is_alive(&self, i: Id) -> bool {
-        let map = self.unionfind.borrow();
-        map[i.0].elem.id == i
+        let map = self.unionfind.read().unwrap();
+        map[i.0].elem.id == i && !self.is_pruned(i)
     }
 
     pub(crate) fn unionfind_iter(&self) -> impl Iterator<Item=(Id, AppliedId)> {
-        let mut map = self.unionfind.borrow_mut();
+        let mut updates = Vec::new();
         let mut out = Vec::new();
 
-        for x in (0..map.len()).map(Id) {
-            let y = self.unionfind_get_impl(x, &mut *map).elem;
-            out.push((x, y));
+        {
+            let map = self.unionfind.read().unwrap();
+            for x in (0..map.len()).map(Id) {
+                let y = self.unionfind_get_impl(x, &map, &mut updates).elem;
+                out.push((x, y));
+            }
         }
+        self.unionfind_write_updates(updates);
 
         out.into_iter()
     }
 
     pub(crate) fn unionfind_len(&self) -> usize {
-        self.unionfind.borrow().len()
+        self.unionfind.read().unwrap().len()
     }
 
     pub(crate) fn find_enode(&self, enode: &L) -> L {
@@ -123,10 +145,10 @@ impl<L: Language, N: Analysis<L>> EGraph<L, N> {
     }
 
     pub fn ids(&self) -> Vec<Id> {
-        let map = self.unionfind.borrow();
+        let map = self.unionfind.read().unwrap();
         (0..map.len())
             .map(Id)
-            .filter(|x| map[x.0].elem.id == *x)
+            .filter(|x| map[x.0].elem.id == *x && !self.is_pruned(*x))
             .collect()
     }
 
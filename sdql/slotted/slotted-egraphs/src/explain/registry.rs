@@ -1,9 +1,18 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::Mutex;
 use crate::*;
 
 #[derive(Clone, Default, Debug)]
-pub(crate) struct ProofRegistry(Rc<RefCell<HashMap<Equation, ProvenEq>>>);
+pub(crate) struct ProofRegistry(Arc<Mutex<RegistryData>>);
+
+#[derive(Clone, Default, Debug)]
+struct RegistryData {
+    // Proofs that were registered before a fork.
+    // These layers are never mutated again, and hence can be shared between all forks.
+    frozen: Vec<Arc<HashMap<Equation, ProvenEq>>>,
+
+    // Proofs that were registered since the last fork.
+    local: HashMap<Equation, ProvenEq>,
+}
 
 fn normalize_eq(eq: &Equation) -> Equation {
     let mut theta = SlotMap::new();
@@ -18,12 +27,19 @@ fn normalize_eq(eq: &Equation) -> Equation {
     eq.apply_slotmap(&theta)
 }
 
+impl RegistryData {
+    fn get(&self, eq: &Equation) -> Option<&ProvenEq> {
+        self.local.get(eq)
+            .or_else(|| self.frozen.iter().rev().find_map(|layer| layer.get(eq)))
+    }
+}
+
 impl ProofRegistry {
     // will not actually insert if the registry already has a proof for it.
     pub(crate) fn insert(&self, peq: ProvenEq) -> ProvenEq {
         let eq = normalize_eq(&peq.equ());
 
-        let mut handle = self.0.borrow_mut();
+        let mut handle = self.0.lock().unwrap();
 
         if let Some(x) = handle.get(&eq) {
             return x.clone();
@@ -31,8 +47,47 @@ impl ProofRegistry {
 
         // TODO add special cases for proofs of the form x=x, and symmetry.
 
-        handle.insert(eq, peq.clone());
+        handle.local.insert(eq, peq.clone());
         peq
     }
-}
 
+    // Returns the registered proof for `eq`, if any. Its equation might be a renaming of `eq`.
+    pub(crate) fn get(&self, eq: &Equation) -> Option<ProvenEq> {
+        let eq = normalize_eq(eq);
+        self.0.lock().unwrap().get(&eq).cloned()
+    }
+
+    // An estimate of the number of bytes allocated by this registry on the heap.
+    // Layers that are shared with forks are counted fully.
+    pub(crate) fn heap_size(&self) -> usize {
+        let handle = self.0.lock().unwrap();
+        let layer_size = |m: &HashMap<Equation, ProvenEq>| {
+            let entry = std::mem::size_of::<(Equation, ProvenEq)>() + 1;
+            let proof = std::mem::size_of::<ProvenEqRaw>() + 2 * std::mem::size_of::<usize>();
+            m.capacity() * entry + m.iter().map(|(eq, peq)| eq.heap_size() + proof + peq.heap_size()).sum::<usize>()
+        };
+        handle.frozen.iter().map(|x| layer_size(x)).sum::<usize>() + layer_size(&handle.local)
+    }
+
+    // Creates an independent registry with the same contents.
+    pub(crate) fn deep_clone(&self) -> ProofRegistry {
+        let data = self.0.lock().unwrap().clone();
+        ProofRegistry(Arc::new(Mutex::new(data)))
+    }
+
+    // Creates an independent registry with the same contents.
+    // In contrast to `deep_clone`, the current proofs are frozen into a layer shared by both registries instead of being copied.
+    pub(crate) fn fork(&self) -> ProofRegistry {
+        let mut handle = self.0.lock().unwrap();
+        if !handle.local.is_empty() {
+            let local = std::mem::take(&mut handle.local);
+            handle.frozen.push(Arc::new(local));
+        }
+
+        let data = RegistryData {
+            frozen: handle.frozen.clone(),
+            local: HashMap::default(),
+        };
+        ProofRegistry(Arc::new(Mutex::new(data)))
+    }
+}
@@ -11,7 +11,7 @@ pub enum Child {
 }
 
 /// A trait to define your Language (i.e. your E-Node type).
-pub trait Language: Debug + Clone + Hash + Eq {
+pub trait Language: Debug + Clone + Hash + Eq + ThreadSafe {
     /// List the mutable references of all child [Slot]s in your E-Node, in order of occurence.
     fn all_slot_occurences_mut(&mut self) -> Vec<&mut Slot>;
 
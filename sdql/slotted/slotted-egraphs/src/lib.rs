@@ -26,6 +26,22 @@ const CHECKS: bool = true;
 #[cfg(not(feature = "checks"))]
 const CHECKS: bool = false;
 
+/// Implemented by all types that can be shared between threads, if the `parallel` feature is enabled.
+///
+/// Without the `parallel` feature, this is implemented by every type.
+#[cfg(feature = "parallel")]
+pub trait ThreadSafe: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync + ?Sized> ThreadSafe for T {}
+
+/// Implemented by all types that can be shared between threads, if the `parallel` feature is enabled.
+///
+/// Without the `parallel` feature, this is implemented by every type.
+#[cfg(not(feature = "parallel"))]
+pub trait ThreadSafe {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> ThreadSafe for T {}
+
 mod slot;
 pub use slot::*;
 
@@ -33,7 +49,7 @@ mod types;
 pub use types::*;
 
 mod parse;
-pub(crate) use parse::*;
+pub use parse::*;
 
 mod lang;
 pub use lang::*;
@@ -1,18 +1,85 @@
 use crate::*;
 
-#[derive(Debug)]
+/// A position in a parsed string.
+///
+/// Lines and columns start at 1. Columns count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// The error returned when parsing a [Pattern] or [RecExpr] fails.
+#[derive(Debug, Clone)]
 pub enum ParseError {
-    TokenState(String),
-    ParseState(Vec<Token>),
-    RemainingRest(Vec<Token>),
-    FromOpFailed(String, Vec<Child>),
-    ExpectedColonEquals(Vec<Token>),
-    ExpectedRBracket(Vec<Token>)
+    /// A `?` or `$` that is not followed by a name.
+    MissingName(Span),
+
+    /// A token that doesn't fit at this position.
+    UnexpectedToken { span: Span, found: String, expected: String },
+
+    /// The input ended too early.
+    UnexpectedEnd { span: Span, expected: String },
+
+    /// [Language::from_op] didn't accept this operator with these children.
+    FromOpFailed { span: Span, op: String, children: Vec<Child> },
+}
+
+impl ParseError {
+    /// Where in the input the error occured.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::MissingName(span) => *span,
+            ParseError::UnexpectedToken { span, .. } => *span,
+            ParseError::UnexpectedEnd { span, .. } => *span,
+            ParseError::FromOpFailed { span, .. } => *span,
+        }
+    }
+
+    // Moves the span of this error, as if the parsed string started at `start` instead of 1:1.
+    pub(crate) fn offset(mut self, start: Span) -> Self {
+        let span = match &mut self {
+            ParseError::MissingName(span) => span,
+            ParseError::UnexpectedToken { span, .. } => span,
+            ParseError::UnexpectedEnd { span, .. } => span,
+            ParseError::FromOpFailed { span, .. } => span,
+        };
+        if span.line == 1 {
+            span.col += start.col - 1;
+        }
+        span.line += start.line - 1;
+        self
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
 }
 
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.span())?;
+        match self {
+            ParseError::MissingName(_) => write!(f, "expected a name after `?` or `$`"),
+            ParseError::UnexpectedToken { found, expected, .. } => write!(f, "expected {expected}, found `{found}`"),
+            ParseError::UnexpectedEnd { expected, .. } => write!(f, "expected {expected}, found end of input"),
+            ParseError::FromOpFailed { op, children, .. } => {
+                let slots = children.iter().filter(|x| matches!(x, Child::Slot(_))).count();
+                let ids = children.len() - slots;
+                write!(f, "invalid operator `{op}` with {slots} slot(s) and {ids} subterm(s)")
+            },
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 #[derive(Debug, Clone)]
 enum Token {
     Slot(Slot), // s42
+    NegSlot(Slot), // -s42
     Ident(String), // map
     PVar(String), // ?x
     ColonEquals, // :=
@@ -20,102 +87,153 @@ enum Token {
     RParen, // )
     LBracket, // [
     RBracket, // ]
+    End, // always the last token.
 }
 
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Slot(s) => write!(f, "{s}"),
+            Token::NegSlot(s) => write!(f, "-{s}"),
+            Token::Ident(op) => write!(f, "{op}"),
+            Token::PVar(p) => write!(f, "?{p}"),
+            Token::ColonEquals => write!(f, ":="),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
+            Token::End => Ok(()),
+        }
+    }
+}
+
+type Tokens<'a> = &'a [(Token, Span)];
+
 fn ident_char(c: char) -> bool {
     if c.is_whitespace() { return false; }
     if "()[]".contains(c) { return false; }
     true
 }
 
-fn crop_ident(s: &str) -> Result<(/*ident*/ &str, /*rest*/ &str), ParseError> {
-    let out = if let Some((i, _)) = s.char_indices().find(|(_, x)| !ident_char(*x)) {
-        (&s[..i], &s[i..])
-    } else {
-        (s, "")
-    };
-
-    if out.0.is_empty() { return Err(ParseError::TokenState(s.to_string())); }
-
-    Ok(out)
+// returns the identifier at the start of `s`.
+fn crop_ident(s: &str) -> Option<&str> {
+    let i = s.char_indices().find(|(_, x)| !ident_char(*x)).map(|(i, _)| i).unwrap_or(s.len());
+    if i == 0 { return None; }
+    Some(&s[..i])
 }
 
-fn tokenize(mut s: &str) -> Result<Vec<Token>, ParseError> {
-    let mut current = String::new();
+fn tokenize(mut s: &str) -> Result<Vec<(Token, Span)>, ParseError> {
     let mut tokens = Vec::new();
+    let mut span = Span { line: 1, col: 1 };
+
+    // moves `s` forward by `n` bytes.
+    let mut bump = |s: &mut &str, n: usize| -> Span {
+        for c in s[..n].chars() {
+            if c == '\n' {
+                span.line += 1;
+                span.col = 1;
+            } else {
+                span.col += 1;
+            }
+        }
+        *s = &s[n..];
+        span
+    };
 
+    let mut current;
     loop {
-        s = s.trim_start();
+        let ws = s.len() - s.trim_start().len();
+        current = bump(&mut s, ws);
         if s.is_empty() { break; }
 
-        if s.starts_with('(') {
-            tokens.push(Token::LParen);
-            s = &s[1..];
+        let (tok, len) = if s.starts_with('(') {
+            (Token::LParen, 1)
         } else if s.starts_with(')') {
-            tokens.push(Token::RParen);
-            s = &s[1..];
+            (Token::RParen, 1)
         } else if s.starts_with('[') {
-            tokens.push(Token::LBracket);
-            s = &s[1..];
+            (Token::LBracket, 1)
         } else if s.starts_with(']') {
-            tokens.push(Token::RBracket);
-            s = &s[1..];
+            (Token::RBracket, 1)
         } else if s.starts_with(":=") {
-            tokens.push(Token::ColonEquals);
-            s = &s[2..];
-        } else if s.starts_with('?') {
-            let (op, rst) = crop_ident(&s[1..])?;
-            tokens.push(Token::PVar(op.to_string()));
-            s = rst;
-        } else if s.starts_with('$') {
-            let (op, rst) = crop_ident(&s[1..])?;
-            tokens.push(Token::Slot(Slot::named(op)));
-            s = rst;
+            (Token::ColonEquals, 2)
+        } else if let Some(rest) = s.strip_prefix('?') {
+            let op = crop_ident(rest).ok_or(ParseError::MissingName(current))?;
+            (Token::PVar(op.to_string()), 1 + op.len())
+        } else if let Some(rest) = s.strip_prefix('$') {
+            let op = crop_ident(rest).ok_or(ParseError::MissingName(current))?;
+            (Token::Slot(Slot::named(op)), 1 + op.len())
+        } else if let Some(rest) = s.strip_prefix("-$") {
+            let op = crop_ident(rest).ok_or(ParseError::MissingName(current))?;
+            (Token::NegSlot(Slot::named(op)), 2 + op.len())
         } else {
-            let (op, rst) = crop_ident(s)?;
-            tokens.push(Token::Ident(op.to_string()));
-            s = rst;
-        }
+            // `s` is non-empty and doesn't start with whitespace or a bracket, so this can't fail.
+            let op = crop_ident(s).unwrap_or(s);
+            (Token::Ident(op.to_string()), op.len())
+        };
+        tokens.push((tok, current));
+        bump(&mut s, len);
     }
+    tokens.push((Token::End, current));
 
     Ok(tokens)
 }
 
+fn unexpected(tok: Tokens, expected: &str) -> ParseError {
+    let (t, span) = &tok[0];
+    let expected = expected.to_string();
+    match t {
+        Token::End => ParseError::UnexpectedEnd { span: *span, expected },
+        _ => ParseError::UnexpectedToken { span: *span, found: t.to_string(), expected },
+    }
+}
+
 // parse:
 impl<L: Language> Pattern<L> {
     pub fn parse(s: &str) -> Result<Self, ParseError> {
         let tok = tokenize(s)?;
-        let (re, rest) = parse_pattern(&tok)?;
-
-        if !rest.is_empty() {
-            return Err(ParseError::RemainingRest(to_vec(rest)));
-        }
-
-        Ok(re)
+        parse_all(&tok)
     }
 }
 
 impl<L: Language> RecExpr<L> {
     pub fn parse(s: &str) -> Result<Self, ParseError> {
-        let pat = Pattern::parse(s)?;
+        let tok = tokenize(s)?;
+        let pat = parse_all(&tok)?;
+
+        // Terms can't contain pattern variables or substitutions.
+        if let Some(i) = tok.iter().position(|(t, _)| matches!(t, Token::PVar(_) | Token::LBracket | Token::NegSlot(_))) {
+            return Err(unexpected(&tok[i..], "a term"));
+        }
+
         Ok(pattern_to_re(&pat))
     }
 }
 
-fn parse_pattern<L: Language>(tok: &[Token]) -> Result<(Pattern<L>, &[Token]), ParseError> {
+fn parse_all<L: Language>(tok: Tokens) -> Result<Pattern<L>, ParseError> {
+    let (pat, rest) = parse_pattern(tok)?;
+
+    if !matches!(rest[0].0, Token::End) {
+        return Err(unexpected(rest, "end of input"));
+    }
+
+    Ok(pat)
+}
+
+// None of the parse functions consume the `End` token, so `tok` is never empty.
+fn parse_pattern<L: Language>(tok: Tokens) -> Result<(Pattern<L>, Tokens), ParseError> {
     let (mut pat, mut tok) = parse_pattern_nosubst(tok)?;
-    while let Some(Token::LBracket) = tok.get(0) {
+    while let Token::LBracket = tok[0].0 {
         tok = &tok[1..];
         let (l, tok2) = parse_pattern(tok)?;
         tok = tok2;
 
-        let Token::ColonEquals = &tok[0] else { return Err(ParseError::ExpectedColonEquals(to_vec(tok))) };
+        let Token::ColonEquals = tok[0].0 else { return Err(unexpected(tok, "`:=`")) };
         tok = &tok[1..];
 
         let (r, tok2) = parse_pattern(tok)?;
         tok = tok2;
 
-        let Token::RBracket = &tok[0] else { return Err(ParseError::ExpectedRBracket(to_vec(tok))) };
+        let Token::RBracket = tok[0].0 else { return Err(unexpected(tok, "`]`")) };
         tok = &tok[1..];
 
         pat = Pattern::Subst(Box::new(pat), Box::new(l), Box::new(r));
@@ -123,48 +241,77 @@ fn parse_pattern<L: Language>(tok: &[Token]) -> Result<(Pattern<L>, &[Token]), P
     Ok((pat, tok))
 }
 
-fn parse_pattern_nosubst<L: Language>(mut tok: &[Token]) -> Result<(Pattern<L>, &[Token]), ParseError> {
-    if let Token::PVar(p) = &tok[0] {
-        let pat = Pattern::PVar(p.to_string());
-        return Ok((pat, &tok[1..]));
-    }
+fn parse_pattern_nosubst<L: Language>(mut tok: Tokens) -> Result<(Pattern<L>, Tokens), ParseError> {
+    match &tok[0].0 {
+        Token::PVar(p) => {
+            tok = &tok[1..];
 
-    if let Token::LParen = tok[0] {
-        tok = &tok[1..];
-
-        let Token::Ident(op) = &tok[0] else { panic!("1"); return Err(ParseError::ParseState(to_vec(tok))) };
-        tok = &tok[1..];
-
-        let mut children = Vec::new();
-        loop {
-            if let Token::RParen = tok[0] { break };
-
-            let (child, tok2) = parse_child(tok)?;
-            tok = tok2;
-            children.push(child);
-        }
-        tok = &tok[1..];
+            // `?x[$1 ...]` is a slot constraint, whereas `?x[(var $1) := ...]` is a substitution.
+            let is_constraint = matches!(tok[0].0, Token::LBracket) && matches!(tok[1].0, Token::Slot(_) | Token::NegSlot(_) | Token::RBracket);
+            if !is_constraint {
+                return Ok((Pattern::PVar(p.to_string()), tok));
+            }
+            tok = &tok[1..];
+
+            let negative = matches!(tok[0].0, Token::NegSlot(_));
+            let mut slots = Vec::new();
+            loop {
+                match (&tok[0].0, negative) {
+                    (Token::RBracket, _) => break,
+                    (Token::Slot(s), false) | (Token::NegSlot(s), true) => slots.push(*s),
+                    (_, false) => return Err(unexpected(tok, "a slot `$x` or `]`")),
+                    (_, true) => return Err(unexpected(tok, "an excluded slot `-$x` or `]`")),
+                }
+                tok = &tok[1..];
+            }
+            tok = &tok[1..];
+
+            let c = if negative { SlotConstraint::Without(slots) } else { SlotConstraint::Only(slots) };
+            Ok((Pattern::ConstrainedPVar(p.to_string(), c), tok))
+        },
+        Token::LParen => {
+            tok = &tok[1..];
+
+            let (Token::Ident(op), span) = &tok[0] else { return Err(unexpected(tok, "an operator")) };
+            tok = &tok[1..];
+
+            let mut children = Vec::new();
+            loop {
+                match tok[0].0 {
+                    Token::RParen => break,
+                    Token::End => return Err(unexpected(tok, "`)`")),
+                    _ => {},
+                }
 
-        let children_mock: Vec<_> = children.iter().map(|x|
-            match x {
-                ChildImpl::Slot(s) => Child::Slot(*s),
-                ChildImpl::Pattern(_) => Child::AppliedId(AppliedId::null()),
+                let (child, tok2) = parse_child(tok)?;
+                tok = tok2;
+                children.push(child);
             }
-        ).collect();
-        let node = L::from_op(op, children_mock.clone()).ok_or_else(|| ParseError::FromOpFailed(op.to_string(), children_mock))?;
-        let children = children.into_iter().filter_map(|x| match x {
-            ChildImpl::Pattern(pat) => Some(pat),
-            ChildImpl::Slot(_) => None,
-        }).collect();
-        let re = Pattern::ENode(node, children);
-        Ok((re, tok))
-    } else {
-        let Token::Ident(op) = &tok[0] else { panic!("2"); return Err(ParseError::ParseState(to_vec(tok))) };
-        tok = &tok[1..];
+            tok = &tok[1..];
 
-        let node = L::from_op(op, vec![]).ok_or_else(|| ParseError::FromOpFailed(op.to_string(), vec![]))?;
-        let pat = Pattern::ENode(node, Vec::new());
-        Ok((pat, tok))
+            let children_mock: Vec<_> = children.iter().map(|x|
+                match x {
+                    ChildImpl::Slot(s) => Child::Slot(*s),
+                    ChildImpl::Pattern(_) => Child::AppliedId(AppliedId::null()),
+                }
+            ).collect();
+            let node = L::from_op(op, children_mock.clone()).ok_or_else(|| ParseError::FromOpFailed { span: *span, op: op.to_string(), children: children_mock })?;
+            let children = children.into_iter().filter_map(|x| match x {
+                ChildImpl::Pattern(pat) => Some(pat),
+                ChildImpl::Slot(_) => None,
+            }).collect();
+            let re = Pattern::ENode(node, children);
+            Ok((re, tok))
+        },
+        Token::Ident(op) => {
+            let span = tok[0].1;
+            tok = &tok[1..];
+
+            let node = L::from_op(op, vec![]).ok_or_else(|| ParseError::FromOpFailed { span, op: op.to_string(), children: vec![] })?;
+            let pat = Pattern::ENode(node, Vec::new());
+            Ok((pat, tok))
+        },
+        _ => Err(unexpected(tok, "a term")),
     }
 }
 
@@ -173,8 +320,8 @@ enum ChildImpl<L: Language> {
     Slot(Slot),
 }
 
-fn parse_child<L: Language>(tok: &[Token]) -> Result<(ChildImpl<L>, &[Token]), ParseError> {
-    if let Token::Slot(slot) = tok[0] {
+fn parse_child<L: Language>(tok: Tokens) -> Result<(ChildImpl<L>, Tokens), ParseError> {
+    if let Token::Slot(slot) = tok[0].0 {
         return Ok((ChildImpl::Slot(slot), &tok[1..]));
     }
 
@@ -210,6 +357,14 @@ impl<L: Language> std::fmt::Display for Pattern<L> {
                 write!(f, ")")
             }
             Pattern::PVar(p) => write!(f, "?{p}"),
+            Pattern::ConstrainedPVar(p, c) => {
+                let (prefix, slots) = match c {
+                    SlotConstraint::Without(slots) => ("-", slots),
+                    SlotConstraint::Only(slots) => ("", slots),
+                };
+                let slots: Vec<String> = slots.iter().map(|s| format!("{prefix}{s}")).collect();
+                write!(f, "?{p}[{}]", slots.join(" "))
+            },
             Pattern::Subst(b, x, t) => write!(f, "{b}[{x} := {t}]"),
         }
     }
@@ -233,7 +388,3 @@ impl<L: Language> std::fmt::Debug for RecExpr<L> {
     }
 }
 
-
-fn to_vec<T: Clone>(t: &[T]) -> Vec<T> {
-    t.iter().cloned().collect()
-}
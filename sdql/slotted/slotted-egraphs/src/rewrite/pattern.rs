@@ -4,13 +4,77 @@ use crate::*;
 /// A Pattern to match against, or as the rhs of a rewrite rule.
 ///
 /// - It supports pattern-variables `?x` to match against anything.
+/// - It supports pattern-variables with a [SlotConstraint] like `?x[-$1]` or `?x[$1 $2]`, which only match e-classes with fitting slots.
 /// - It supports (on the rhs) substitutions `b[x := t]` to substitute natively.
 pub enum Pattern<L: Language> {
     ENode(L, Vec<Pattern<L>>),
     PVar(String), // ?x
+    ConstrainedPVar(String, SlotConstraint), // ?x[-$1] or ?x[$1 $2]
     Subst(Box<Pattern<L>>, Box<Pattern<L>>, Box<Pattern<L>>), // Subst(b, x, t) means `b[x := t]`
 }
 
+#[derive(Clone, Hash, PartialEq, Eq, Debug)]
+/// Restricts which pattern slots a pattern-variable may mention.
+///
+/// Slots of the matched e-class that don't correspond to any slot of the pattern count as "other" slots.
+pub enum SlotConstraint {
+    /// `?x[-$1 -$2]`: `?x` must not mention `$1` or `$2`.
+    Without(Vec<Slot>),
+
+    /// `?x[$1 $2]`: `?x` may only mention `$1` and `$2`.
+    Only(Vec<Slot>),
+}
+
+impl SlotConstraint {
+    /// Whether a pattern-variable may mention the pattern slot `s`.
+    ///
+    /// `None` stands for a slot that doesn't occur in the pattern.
+    pub fn allows(&self, s: Option<Slot>) -> bool {
+        match self {
+            SlotConstraint::Without(v) => s.is_none_or(|s| !v.contains(&s)),
+            SlotConstraint::Only(v) => s.is_some_and(|s| v.contains(&s)),
+        }
+    }
+}
+
+/// Returns all constrained pattern-variables in `pat`, together with their constraints.
+pub fn slot_constraints<L: Language>(pat: &Pattern<L>) -> Vec<(&str, &SlotConstraint)> {
+    match pat {
+        Pattern::ENode(_, children) => children.iter().flat_map(slot_constraints).collect(),
+        Pattern::PVar(_) => Vec::new(),
+        Pattern::ConstrainedPVar(v, c) => vec![(v, c)],
+        Pattern::Subst(b, x, t) => [b, x, t].into_iter().flat_map(|p| slot_constraints(p)).collect(),
+    }
+}
+
+/// Returns the names of all pattern-variables in `pat`.
+pub fn pattern_vars<L: Language>(pat: &Pattern<L>) -> HashSet<String> {
+    match pat {
+        Pattern::ENode(_, children) => children.iter().flat_map(pattern_vars).collect(),
+        Pattern::PVar(v) | Pattern::ConstrainedPVar(v, _) => [v.clone()].into_iter().collect(),
+        Pattern::Subst(b, x, t) => [b, x, t].into_iter().flat_map(|p| pattern_vars(p)).collect(),
+    }
+}
+
+/// Returns the slots that occur freely in `pat`, i.e. that are not bound by a binder within `pat`.
+pub fn pattern_free_slots<L: Language>(pat: &Pattern<L>) -> HashSet<Slot> {
+    match pat {
+        Pattern::ENode(n, children) => {
+            let mut out: HashSet<Slot> = n.public_slot_occurences().into_iter().collect();
+            for c in children {
+                out.extend(pattern_free_slots(c));
+            }
+            for s in n.private_slot_occurences() {
+                out.remove(&s);
+            }
+            out
+        },
+        Pattern::PVar(_) | Pattern::ConstrainedPVar(..) => HashSet::default(),
+        // `x` stands for the variable that is substituted away.
+        Pattern::Subst(b, x, t) => &(&pattern_free_slots(b) - &pattern_free_slots(x)) | &pattern_free_slots(t),
+    }
+}
+
 // We write this as pattern[subst] for short.
 #[cfg_attr(feature = "trace", instrument(level = "trace", skip_all))]
 pub fn pattern_subst<L: Language, N: Analysis<L>>(eg: &mut EGraph<L, N>, pattern: &Pattern<L>, subst: &Subst) -> AppliedId {
@@ -26,7 +90,7 @@ pub fn pattern_subst<L: Language, N: Analysis<L>>(eg: &mut EGraph<L, N>, pattern
             }
             eg.add_syn(n)
         },
-        Pattern::PVar(v) => {
+        Pattern::PVar(v) | Pattern::ConstrainedPVar(v, _) => {
             subst.get(v)
                  .unwrap_or_else(|| panic!("encountered `?{v}` in pattern, but it is missing in the `subst`"))
                  .clone()
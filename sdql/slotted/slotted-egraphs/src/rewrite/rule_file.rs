@@ -0,0 +1,192 @@
+use crate::*;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+type Condition<L, N> = Arc<dyn Fn(&Subst, &EGraph<L, N>) -> bool>;
+
+/// The named conditions that a rule file can refer to using `if name`.
+pub struct ConditionRegistry<L: Language, N: Analysis<L> = ()> {
+    conds: HashMap<String, Condition<L, N>>,
+}
+
+impl<L: Language, N: Analysis<L>> Default for ConditionRegistry<L, N> {
+    fn default() -> Self {
+        ConditionRegistry { conds: HashMap::default() }
+    }
+}
+
+impl<L: Language, N: Analysis<L>> ConditionRegistry<L, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `cond` under the given `name`, replacing any previous condition of that name.
+    pub fn add(&mut self, name: &str, cond: impl Fn(&Subst, &EGraph<L, N>) -> bool + 'static) {
+        self.conds.insert(name.to_string(), Arc::new(cond));
+    }
+}
+
+/// The error returned when loading a rule file fails.
+#[derive(Debug, Clone)]
+pub struct RuleFileError {
+    /// The name of the rule file.
+    pub file: String,
+
+    /// The line of the rule file at which the error occured, starting at 1.
+    ///
+    /// This is 0 for errors that concern the whole file.
+    pub line: usize,
+
+    pub kind: RuleFileErrorKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum RuleFileErrorKind {
+    /// The rule file couldn't be read.
+    Io(String),
+
+    /// A rule that doesn't start with `name:`.
+    MissingName,
+
+    /// A rule without `=>` or `<=>`, or with more than one of them.
+    MissingArrow,
+
+    /// An `if` that isn't followed by conditions.
+    MissingCondition,
+
+    /// The lhs or rhs of a rule is not a valid [Pattern].
+    ///
+    /// The span of the [ParseError] is relative to the rule file.
+    Pattern(ParseError),
+
+    /// A condition that is not part of the [ConditionRegistry].
+    UnknownCondition(String),
+
+    /// A rule that is invalid, see [Rewrite::try_new].
+    InvalidRule(RuleError),
+
+    /// Two rules with the same name.
+    /// Note that `name: a <=> b` defines both `name` and `name-rev`.
+    DuplicateName(String),
+}
+
+impl Display for RuleFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let file = &self.file;
+        let line = self.line;
+        match &self.kind {
+            RuleFileErrorKind::Io(msg) => write!(f, "{file}: {msg}"),
+            RuleFileErrorKind::MissingName => write!(f, "{file}:{line}: expected a rule of the form `name: lhs => rhs`"),
+            RuleFileErrorKind::MissingArrow => write!(f, "{file}:{line}: expected exactly one `=>` or `<=>`"),
+            RuleFileErrorKind::MissingCondition => write!(f, "{file}:{line}: expected a condition after `if`"),
+            RuleFileErrorKind::Pattern(err) => write!(f, "{file}:{err}"),
+            RuleFileErrorKind::InvalidRule(e) => write!(f, "{file}:{line}: {e}"),
+            RuleFileErrorKind::UnknownCondition(c) => write!(f, "{file}:{line}: unknown condition `{c}`"),
+            RuleFileErrorKind::DuplicateName(name) => write!(f, "{file}:{line}: duplicate rule name `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for RuleFileError {}
+
+/// Reads the rule file at `path`, see [parse_rules].
+pub fn load_rules<L: Language + 'static, N: Analysis<L> + 'static>(path: impl AsRef<Path>, conds: &ConditionRegistry<L, N>) -> Result<Vec<Rewrite<L, N>>, RuleFileError> {
+    let path = path.as_ref();
+    let file = path.display().to_string();
+    let s = std::fs::read_to_string(path).map_err(|e| RuleFileError { file: file.clone(), line: 0, kind: RuleFileErrorKind::Io(e.to_string()) })?;
+    parse_rules(&s, &file, conds)
+}
+
+/// Parses rewrite rules from the contents `s` of a rule file.
+///
+/// Every non-empty line that doesn't start with `#` is a rule of one of the forms
+/// ```text
+/// name: lhs => rhs
+/// name: lhs <=> rhs
+/// name: lhs => rhs if cond1, cond2
+/// ```
+/// where `lhs` and `rhs` are [Pattern]s, and the conditions are looked up in `conds`.
+/// A rule with `<=>` additionally defines the reverse rule `name-rev`, see [reverse_rule_name].
+///
+/// The `file` name is only used for error messages.
+pub fn parse_rules<L: Language + 'static, N: Analysis<L> + 'static>(s: &str, file: &str, conds: &ConditionRegistry<L, N>) -> Result<Vec<Rewrite<L, N>>, RuleFileError> {
+    let mut out: Vec<Rewrite<L, N>> = Vec::new();
+    let mut names = HashSet::default();
+
+    for (i, line) in s.lines().enumerate() {
+        let line_nr = i + 1;
+        let err = |kind| RuleFileError { file: file.to_string(), line: line_nr, kind };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') { continue; }
+
+        let Some(colon) = line.find(':') else { return Err(err(RuleFileErrorKind::MissingName)) };
+        let name = line[..colon].trim();
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || "()[]".contains(c)) {
+            return Err(err(RuleFileErrorKind::MissingName));
+        }
+
+        // The words after the `:`, together with their byte offsets in `line`.
+        let ws: Vec<(usize, &str)> = words(&line[colon+1..]).into_iter().map(|(j, w)| (j + colon + 1, w)).collect();
+
+        let arrows: Vec<usize> = (0..ws.len()).filter(|j| ws[*j].1 == "=>" || ws[*j].1 == "<=>").collect();
+        let [arrow] = arrows[..] else { return Err(err(RuleFileErrorKind::MissingArrow)) };
+        let bidirectional = ws[arrow].1 == "<=>";
+        let cond_start = (arrow+1..ws.len()).find(|j| ws[*j].1 == "if").unwrap_or(ws.len());
+
+        let pattern = |from: usize, to: usize| -> Result<Pattern<L>, RuleFileError> {
+            // An empty pattern is reported at the word after it.
+            let start = ws.get(from).map_or(line.len(), |(a, _)| *a);
+            let end = if from < to { ws[to-1].0 + ws[to-1].1.len() } else { start };
+            let col = line[..start].chars().count() + 1;
+            Pattern::parse(&line[start..end]).map_err(|e| err(RuleFileErrorKind::Pattern(e.offset(Span { line: line_nr, col }))))
+        };
+        let lhs = pattern(0, arrow)?;
+        let rhs = pattern(arrow+1, cond_start)?;
+
+        let mut rule_conds: Vec<Condition<L, N>> = Vec::new();
+        if cond_start < ws.len() {
+            let (j, _) = ws[cond_start];
+            let text = &line[j + "if".len()..];
+            for c in text.split(',') {
+                let c = c.trim();
+                if c.is_empty() { return Err(err(RuleFileErrorKind::MissingCondition)); }
+                let Some(f) = conds.conds.get(c) else { return Err(err(RuleFileErrorKind::UnknownCondition(c.to_string()))) };
+                rule_conds.push(f.clone());
+            }
+        }
+
+        let mut rules = vec![(name.to_string(), lhs.clone(), rhs.clone())];
+        if bidirectional {
+            rules.push((reverse_rule_name(name), rhs, lhs));
+        }
+        for (name, lhs, rhs) in rules {
+            if !names.insert(name.clone()) {
+                return Err(err(RuleFileErrorKind::DuplicateName(name)));
+            }
+            let rule = Rule { name, lhs, rhs };
+            check_rule(&rule).map_err(|e| err(RuleFileErrorKind::InvalidRule(e)))?;
+            let rule_conds = rule_conds.clone();
+            out.push(Rewrite::from_patterns(&rule.name, rule.lhs, rule.rhs, move |subst, eg| rule_conds.iter().all(|c| c(subst, eg))));
+        }
+    }
+
+    Ok(out)
+}
+
+// The whitespace-separated words of `s`, together with their byte offsets.
+fn words(s: &str) -> Vec<(usize, &str)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in s.char_indices().chain([(s.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (true, Some(j)) => {
+                out.push((j, &s[j..i]));
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => {},
+        }
+    }
+    out
+}
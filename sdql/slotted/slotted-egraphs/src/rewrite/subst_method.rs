@@ -1,12 +1,14 @@
 use crate::*;
 
 /// Specifies a certain implementation of how substitution `b[x := t]` is implemented internally.
-pub trait SubstMethod<L: Language, N: Analysis<L>> {
+pub trait SubstMethod<L: Language, N: Analysis<L>>: ThreadSafe {
     fn new_boxed() -> Box<dyn SubstMethod<L, N>> where Self: Sized;
+    fn box_clone(&self) -> Box<dyn SubstMethod<L, N>>;
     fn subst(&mut self, b: AppliedId, x: AppliedId, t: AppliedId, eg: &mut EGraph<L, N>) -> AppliedId;
 }
 
 /// A [SubstMethod] that uses the [EGraph::get_syn_expr] of an e-class to do substitution on it.
+#[derive(Clone)]
 pub struct SynExprSubst;
 
 impl<L: Language, N: Analysis<L>> SubstMethod<L, N> for SynExprSubst {
@@ -14,6 +16,10 @@ impl<L: Language, N: Analysis<L>> SubstMethod<L, N> for SynExprSubst {
         Box::new(SynExprSubst)
     }
 
+    fn box_clone(&self) -> Box<dyn SubstMethod<L, N>> {
+        Box::new(self.clone())
+    }
+
     fn subst(&mut self, b: AppliedId, x: AppliedId, t: AppliedId, eg: &mut EGraph<L, N>) -> AppliedId {
         let term = eg.get_syn_expr(&eg.synify_app_id(b));
         do_term_subst(eg, &term, &x, &t)
@@ -21,6 +27,7 @@ impl<L: Language, N: Analysis<L>> SubstMethod<L, N> for SynExprSubst {
 }
 
 /// A [SubstMethod] that extracts the smallest term (measured by [AstSize]) of an e-class to do substitution on it.
+#[derive(Clone)]
 pub struct ExtractionSubst;
 
 impl<L: Language, N: Analysis<L>> SubstMethod<L, N> for ExtractionSubst {
@@ -28,6 +35,10 @@ impl<L: Language, N: Analysis<L>> SubstMethod<L, N> for ExtractionSubst {
         Box::new(ExtractionSubst)
     }
 
+    fn box_clone(&self) -> Box<dyn SubstMethod<L, N>> {
+        Box::new(self.clone())
+    }
+
     fn subst(&mut self, b: AppliedId, x: AppliedId, t: AppliedId, eg: &mut EGraph<L, N>) -> AppliedId {
         let term = ast_size_extract::<L, N>(&b, eg);
         do_term_subst(eg, &term, &x, &t)
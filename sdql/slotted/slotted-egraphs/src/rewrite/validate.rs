@@ -0,0 +1,97 @@
+use crate::*;
+use std::fmt::{self, Display, Formatter};
+
+/// The reason why a rewrite rule was rejected.
+#[derive(Debug, Clone)]
+pub enum RuleError {
+    /// The lhs or rhs is not a valid [Pattern].
+    Parse { rule: String, err: ParseError },
+
+    /// The rhs uses a pattern-variable that doesn't occur on the lhs.
+    UnboundVar { rule: String, var: String },
+
+    /// The rhs mentions a free slot that is not a free slot of the lhs.
+    UnboundSlot { rule: String, slot: Slot },
+
+    /// A binder on the rhs uses the same slot as a free slot of the lhs, and would hence capture it.
+    ShadowedSlot { rule: String, slot: Slot },
+
+    /// The lhs contains a substitution `b[x := t]`, which can't be matched against.
+    SubstOnLhs { rule: String },
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Parse { rule, err } => write!(f, "rule '{rule}': {err}"),
+            RuleError::UnboundVar { rule, var } => write!(f, "rule '{rule}': the rhs uses `?{var}`, which doesn't occur on the lhs"),
+            RuleError::UnboundSlot { rule, slot } => write!(f, "rule '{rule}': the rhs mentions the free slot `{slot}`, which is not free on the lhs"),
+            RuleError::ShadowedSlot { rule, slot } => write!(f, "rule '{rule}': a binder on the rhs shadows the free slot `{slot}` of the lhs"),
+            RuleError::SubstOnLhs { rule } => write!(f, "rule '{rule}': substitutions `b[x := t]` are only allowed on the rhs"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// The name of the reverse direction of the rule `name`, as used by [Rewrite::new_bidir].
+pub fn reverse_rule_name(name: &str) -> String {
+    format!("{name}-rev")
+}
+
+pub(crate) fn parse_rule<L: Language>(rule: &str, a: &str, b: &str) -> Result<Rule<L>, RuleError> {
+    let parse = |s| Pattern::parse(s).map_err(|err| RuleError::Parse { rule: rule.to_string(), err });
+    Ok(Rule { name: rule.to_string(), lhs: parse(a)?, rhs: parse(b)? })
+}
+
+// Checks that everything on the rhs of `rule` is bound by its lhs, and that nothing gets captured.
+// Errors are reported in a deterministic order.
+pub(crate) fn check_rule<L: Language>(rule: &Rule<L>) -> Result<(), RuleError> {
+    if contains_subst(&rule.lhs) {
+        return Err(RuleError::SubstOnLhs { rule: rule.name.clone() });
+    }
+
+    let lhs_vars = pattern_vars(&rule.lhs);
+    let mut rhs_vars: Vec<String> = pattern_vars(&rule.rhs).into_iter().collect();
+    rhs_vars.sort();
+    if let Some(var) = rhs_vars.into_iter().find(|v| !lhs_vars.contains(v)) {
+        return Err(RuleError::UnboundVar { rule: rule.name.clone(), var });
+    }
+
+    let lhs_slots = pattern_free_slots(&rule.lhs);
+    let mut rhs_slots: Vec<Slot> = pattern_free_slots(&rule.rhs).into_iter().collect();
+    rhs_slots.sort();
+    if let Some(slot) = rhs_slots.into_iter().find(|s| !lhs_slots.contains(s)) {
+        return Err(RuleError::UnboundSlot { rule: rule.name.clone(), slot });
+    }
+
+    let mut rhs_binders: Vec<Slot> = pattern_binders(&rule.rhs).into_iter().collect();
+    rhs_binders.sort();
+    if let Some(slot) = rhs_binders.into_iter().find(|s| lhs_slots.contains(s)) {
+        return Err(RuleError::ShadowedSlot { rule: rule.name.clone(), slot });
+    }
+
+    Ok(())
+}
+
+fn pattern_binders<L: Language>(pat: &Pattern<L>) -> HashSet<Slot> {
+    match pat {
+        Pattern::ENode(n, children) => {
+            let mut out: HashSet<Slot> = n.private_slot_occurences().into_iter().collect();
+            for c in children {
+                out.extend(pattern_binders(c));
+            }
+            out
+        },
+        Pattern::PVar(_) | Pattern::ConstrainedPVar(..) => HashSet::default(),
+        Pattern::Subst(b, x, t) => &(&pattern_binders(b) | &pattern_binders(x)) | &pattern_binders(t),
+    }
+}
+
+fn contains_subst<L: Language>(pat: &Pattern<L>) -> bool {
+    match pat {
+        Pattern::ENode(_, children) => children.iter().any(contains_subst),
+        Pattern::PVar(_) | Pattern::ConstrainedPVar(..) => false,
+        Pattern::Subst(..) => true,
+    }
+}
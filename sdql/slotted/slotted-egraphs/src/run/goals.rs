@@ -0,0 +1,71 @@
+use crate::*;
+
+/// A goal of equality saturation, see [Goals].
+#[derive(Clone, Debug)]
+pub enum Goal<L: Language> {
+    /// Reached, once both [AppliedId]s are equal, see [EGraph::eq].
+    Equal(AppliedId, AppliedId),
+
+    /// Reached, once the term is represented by the e-class of the [AppliedId], see [lookup_rec_expr].
+    ///
+    /// In contrast to [Goal::Equal], the term doesn't need to be added to the e-graph.
+    Term(AppliedId, RecExpr<L>),
+}
+
+impl<L: Language> Goal<L> {
+    /// Whether this goal is reached in `eg`.
+    ///
+    /// Unions are taken into account immediately, but equalities implied by congruence only after [EGraph::rebuild].
+    pub fn is_reached<N: Analysis<L>>(&self, eg: &EGraph<L, N>) -> bool {
+        match self {
+            Goal::Equal(a, b) => eg.eq(a, b),
+            Goal::Term(i, re) => lookup_rec_expr(re, eg).is_some_and(|j| eg.eq(i, &j)),
+        }
+    }
+}
+
+/// Whether equality saturation stops once all goals, or once any goal is reached.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GoalMode {
+    All,
+    Any,
+}
+
+/// A set of goals that stops equality saturation once it is reached, see [run_eqsat_goals].
+#[derive(Clone, Debug)]
+pub struct Goals<L: Language> {
+    pub goals: Vec<Goal<L>>,
+    pub mode: GoalMode,
+}
+
+impl<L: Language> Goals<L> {
+    /// Stops once all of the `goals` are reached.
+    pub fn all(goals: Vec<Goal<L>>) -> Self {
+        Goals { goals, mode: GoalMode::All }
+    }
+
+    /// Stops once any of the `goals` is reached.
+    pub fn any(goals: Vec<Goal<L>>) -> Self {
+        Goals { goals, mode: GoalMode::Any }
+    }
+
+    /// Never stops.
+    pub fn none() -> Self {
+        Self::all(Vec::new())
+    }
+
+    /// The indices of the reached goals, in increasing order.
+    pub fn reached<N: Analysis<L>>(&self, eg: &EGraph<L, N>) -> Vec<usize> {
+        (0..self.goals.len()).filter(|i| self.goals[*i].is_reached(eg)).collect()
+    }
+
+    /// Whether the goals with the indices `reached` suffice to stop.
+    ///
+    /// An empty set of goals never suffices.
+    pub fn is_done(&self, reached: &[usize]) -> bool {
+        match self.mode {
+            GoalMode::All => !self.goals.is_empty() && reached.len() == self.goals.len(),
+            GoalMode::Any => !reached.is_empty(),
+        }
+    }
+}
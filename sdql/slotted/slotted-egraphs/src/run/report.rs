@@ -4,6 +4,8 @@ pub enum StopReason {
     Saturated,
     IterationLimit,
     TimeLimit,
+    MemoryLimit,
+    GoalsReached,
     Other(String),
 }
 
@@ -13,4 +15,10 @@ pub struct Report {
     pub egraph_nodes: usize,
     pub egraph_classes: usize,
     pub total_time: f64,
+
+    // The part of `total_time` that was spent in `EGraph::rebuild`.
+    pub rebuild_time: f64,
+
+    // The indices of the goals that were reached, see `Goals::reached`.
+    pub reached_goals: Vec<usize>,
 }
\ No newline at end of file
@@ -1,25 +1,66 @@
 use crate::*;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 // TODO: Turn this into a nicer interface like egg's `Runner`.
 
+/// The limits of [run_eqsat_goals].
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// The maximal number of times the rewrites are applied.
+    pub iter_limit: usize,
+
+    /// In seconds.
+    pub time_limit: usize,
+
+    /// In bytes, see [EGraph::memory_usage].
+    pub memory_limit: Option<usize>,
+}
+
+impl Limits {
+    /// Limits without a memory limit.
+    pub fn new(iter_limit: usize, time_limit: usize) -> Self {
+        Limits { iter_limit, time_limit, memory_limit: None }
+    }
+
+    /// Additionally stops with [StopReason::MemoryLimit] once the e-graph holds `bytes` bytes, see [EGraph::memory_usage].
+    pub fn with_memory_limit(self, bytes: usize) -> Self {
+        Limits { memory_limit: Some(bytes), ..self }
+    }
+}
+
+// See `run_eqsat_goals` for goals and memory limits.
 pub fn run_eqsat<L: Language, N: Analysis<L>, F>(
     egraph: &mut EGraph<L, N>, 
     rws: Vec<Rewrite<L, N>>, 
     iter_limit: usize, 
     time_limit: usize, 
+    hook: F
+) -> Report where F: FnMut(&mut EGraph<L, N>) -> Result<(), String> + 'static {
+    // `run_eqsat` has always applied the rewrites once more than its `iter_limit`.
+    run_eqsat_goals(egraph, &rws, &Goals::none(), Limits::new(iter_limit.saturating_add(1), time_limit), hook)
+}
+
+// Like `run_eqsat`, but additionally stops with `StopReason::GoalsReached` once the `goals` are reached.
+// The goals are checked after each applied rewrite, and again after each rebuild.
+// Like in egg, `Report::iterations` counts every round of rewrites, including the one that reached the goals.
+pub fn run_eqsat_goals<L: Language, N: Analysis<L>, F>(
+    egraph: &mut EGraph<L, N>, 
+    rws: &[Rewrite<L, N>], 
+    goals: &Goals<L>,
+    limits: Limits,
     mut hook: F
 ) -> Report where F: FnMut(&mut EGraph<L, N>) -> Result<(), String> + 'static {
     let start_time = Instant::now();
     let mut iterations = 0;
     let stop_reason: StopReason;
+    let mut rebuild_time = Duration::ZERO;
+    let mut reached_goals = goals.reached(egraph);
+    let mut did_change = true;
 
     loop {
-        let did_change = apply_rewrites(egraph, &rws);
-        
-        match hook(egraph) {
-            Ok(_) => (),
-            Err(msg) => { stop_reason = StopReason::Other(msg.to_string()); break }
+        if goals.is_done(&reached_goals) {
+            stop_reason = StopReason::GoalsReached;
+            break
         }
 
         if !did_change {
@@ -27,17 +68,29 @@ pub fn run_eqsat<L: Language, N: Analysis<L>, F>(
             break
         }
 
-        if iterations >= iter_limit {
+        if iterations >= limits.iter_limit {
             stop_reason = StopReason::IterationLimit;
             break
         }
 
-        if start_time.elapsed().as_secs() >= time_limit.try_into().unwrap() {
+        if start_time.elapsed().as_secs() >= limits.time_limit.try_into().unwrap() {
             stop_reason = StopReason::TimeLimit;
             break
         }
-        
+
+        if limits.memory_limit.is_some_and(|limit| egraph.memory_usage().total() >= limit) {
+            stop_reason = StopReason::MemoryLimit;
+            break
+        }
+
+        did_change = apply_rewrites_until(egraph, rws, &mut rebuild_time, |eg| goals.is_done(&goals.reached(eg)));
+        reached_goals = goals.reached(egraph);
         iterations += 1;
+        
+        match hook(egraph) {
+            Ok(_) => (),
+            Err(msg) => { stop_reason = StopReason::Other(msg.to_string()); break }
+        }
     }
 
     Report {
@@ -45,6 +98,8 @@ pub fn run_eqsat<L: Language, N: Analysis<L>, F>(
         stop_reason,
         egraph_nodes: egraph.total_number_of_nodes(),
         egraph_classes: egraph.ids().len(),
-        total_time: start_time.elapsed().as_secs_f64()
+        total_time: start_time.elapsed().as_secs_f64(),
+        rebuild_time: rebuild_time.as_secs_f64(),
+        reached_goals,
     }
 }
@@ -1,6 +1,7 @@
 use crate::*;
 use std::fmt::*;
-use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 /// Slots represent Variable names.
@@ -13,29 +14,25 @@ pub struct Slot(u64);
 // %4 = 2 -> named
 // %4 = 3 -> <unused>
 struct SlotTable {
-    fresh_idx: u64,
     named_vec: Vec<String>,
     named_map: HashMap<String, u64>,
 }
 
-thread_local! {
-    static SLOT_TABLE: RefCell<SlotTable> = RefCell::new(SlotTable {
-        fresh_idx: 1,
-        named_vec: Vec::default(),
-        named_map: HashMap::default(),
-    });
-}
+// Both the fresh counter and the named slots are process-global,
+// so that a Slot means the same thing on every thread.
+static FRESH_IDX: AtomicU64 = AtomicU64::new(1);
+
+static SLOT_TABLE: LazyLock<RwLock<SlotTable>> = LazyLock::new(|| RwLock::new(SlotTable {
+    named_vec: Vec::default(),
+    named_map: HashMap::default(),
+}));
 
 impl Slot {
     /// Generates a fresh slot.
     ///
     /// Any slot returned from this function has never been constructed before.
     pub fn fresh() -> Self {
-        SLOT_TABLE.with_borrow_mut(|tab| {
-            let old_val = tab.fresh_idx;
-            tab.fresh_idx += 4;
-            Slot(old_val)
-        })
+        Slot(FRESH_IDX.fetch_add(4, Ordering::Relaxed))
     }
 
     /// Generates a numeric slot like `$42`
@@ -45,31 +42,34 @@ impl Slot {
 
     /// Generates a named slot like `$xyz`
     pub fn named(s: &str) -> Slot {
-        if let Ok(x) = s.parse::<u64>() {
-            return Slot(x*4); // numeric
+        // Indices that are too large to be encoded are treated like any other name.
+        if let Some(x) = s.parse::<u64>().ok().and_then(|x| x.checked_mul(4)) {
+            return Slot(x); // numeric
         }
 
-        SLOT_TABLE.with_borrow_mut(|tab| {
-            if s.starts_with("f") {
-                if let Ok(x) = s[1..].parse::<u64>() {
-                    let out = x*4+1;
-                    if tab.fresh_idx <= out {
-                        tab.fresh_idx = out+4;
-                    }
-                    return Slot(out); // fresh
-                }
+        if let Some(rest) = s.strip_prefix("f") {
+            if let Some(out) = rest.parse::<u64>().ok().and_then(|x| x.checked_mul(4)?.checked_add(1)) {
+                FRESH_IDX.fetch_max(out.saturating_add(4), Ordering::Relaxed);
+                return Slot(out); // fresh
             }
+        }
 
-            if let Some(x) = tab.named_map.get(s) {
-                return Slot(*x); // cached named
-            }
+        if let Some(x) = SLOT_TABLE.read().unwrap().named_map.get(s) {
+            return Slot(*x); // cached named
+        }
+
+        let mut tab = SLOT_TABLE.write().unwrap();
+
+        // Another thread might have inserted it in the meantime.
+        if let Some(x) = tab.named_map.get(s) {
+            return Slot(*x); // cached named
+        }
 
-            let i = tab.named_vec.len() as u64;
-            let i = 4*i + 2;
-            tab.named_vec.push(s.to_string());
-            tab.named_map.insert(s.to_string(), i);
-            Slot(i) // new named
-        })
+        let i = tab.named_vec.len() as u64;
+        let i = 4*i + 2;
+        tab.named_vec.push(s.to_string());
+        tab.named_map.insert(s.to_string(), i);
+        Slot(i) // new named
     }
 }
 
@@ -86,9 +86,8 @@ impl Display for Slot {
             // named:
             2 => {
                 let idx = ((u-2)/4) as usize;
-                SLOT_TABLE.with_borrow(|tab| {
-                    write!(f, "${}", tab.named_vec[idx])
-                })
+                let tab = SLOT_TABLE.read().unwrap();
+                write!(f, "${}", tab.named_vec[idx])
             }
 
             // unused:
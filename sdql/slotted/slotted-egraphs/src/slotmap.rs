@@ -31,6 +31,11 @@ impl SlotMap {
         self.map.is_empty()
     }
 
+    // The number of bytes allocated by this SlotMap on the heap.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.map.capacity() * std::mem::size_of::<(Slot, Slot)>()
+    }
+
     fn search(&self, l: Slot) -> Result<usize, usize> {
         self.map.binary_search_by_key(&l, |(x, _)| *x)
     }
@@ -0,0 +1,30 @@
+use crate::*;
+
+#[test]
+fn bidirectional_rewrites() {
+    let rws: Vec<Rewrite<Arith>> = Rewrite::new_bidir("assoc", "(add ?a (add ?b ?c))", "(add (add ?a ?b) ?c)");
+    let names: Vec<&str> = rws.iter().map(|rw| rw.rule().unwrap().name.as_str()).collect();
+    assert_eq!(names, ["assoc", "assoc-rev"]);
+
+    // binders are fine, as long as the free slots agree.
+    Rewrite::<Arith>::new_bidir("let-add", "(let $1 ?e (add ?a ?b))", "(add (let $1 ?e ?a) (let $1 ?e ?b))");
+    Rewrite::<Arith>::new_bidir("var", "(add (var $0) ?a)", "(add ?a (var $0))");
+
+    let mut eg: EGraph<Arith> = EGraph::new();
+    let i = eg.add_expr(parse_arith("(add (add (var $0) (var $1)) (var $2))"));
+    apply_rewrites(&mut eg, &rws[1..]);
+    let j = eg.add_expr(parse_arith("(add (var $0) (add (var $1) (var $2)))"));
+    assert!(eg.eq(&i, &j));
+}
+
+#[test]
+#[should_panic(expected = "rule 'drop-rev': the rhs uses `?c`, which doesn't occur on the lhs")]
+fn bidirectional_rewrite_with_unbound_var() {
+    Rewrite::<Arith>::new_bidir("drop", "(mul ?a (add ?b ?c))", "(mul ?a ?b)");
+}
+
+#[test]
+#[should_panic(expected = "rule 'forget': the rhs mentions the free slot `$0`, which is not free on the lhs")]
+fn bidirectional_rewrite_with_unbound_slot() {
+    Rewrite::<Arith>::new_bidir("forget", "(lam $0 (var $0))", "(lam $1 (var $0))");
+}
@@ -0,0 +1,22 @@
+use crate::*;
+
+// Shared setup of the tests below `arith`.
+
+pub fn parse_arith(s: &str) -> RecExpr<Arith> {
+    RecExpr::parse(s).unwrap()
+}
+
+// Adds `start` to a new e-graph and applies the arith rules `iters` times.
+pub fn saturate_arith(start: &str, iters: usize) -> (EGraph<Arith>, AppliedId) {
+    let mut eg = EGraph::new();
+    let i = eg.add_expr(parse_arith(start));
+    for _ in 0..iters {
+        rewrite_arith(&mut eg);
+    }
+    (eg, i)
+}
+
+// The rules of `arith_rules`, as checked by proof certificates.
+pub fn arith_rule_defs() -> Vec<Rule<Arith>> {
+    arith_rules().iter().filter_map(|rw| rw.rule().cloned()).collect()
+}
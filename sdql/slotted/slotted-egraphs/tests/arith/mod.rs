@@ -15,6 +15,16 @@ pub use my_cost::*;
 mod const_prop;
 pub use const_prop::*;
 
+mod fixture;
+pub use fixture::*;
+
+mod bidir;
+mod expl;
+mod facts;
+mod prune;
+mod rule_file;
+mod runner;
+
 #[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Arith {
     // lambda calculus:
@@ -0,0 +1,72 @@
+use crate::*;
+
+#[test]
+fn remove_enode() {
+    let mut eg = EGraph::<Arith>::new();
+    let v = eg.add_expr(parse_arith("(var $0)"));
+    let two = eg.add_expr(parse_arith("2"));
+    let a = eg.add(Arith::Add(v.clone(), two.clone()));
+    rewrite_arith(&mut eg);
+
+    let comm = lookup_rec_expr(&parse_arith("(add 2 (var $0))"), &eg).unwrap();
+    assert!(eg.eq(&a, &comm));
+
+    assert!(eg.remove(&Arith::Add(v.clone(), two.clone())));
+    assert!(!eg.remove(&Arith::Add(v.clone(), two.clone())));
+    assert!(eg.lookup(&Arith::Add(v, two)).is_none());
+
+    // The equality stays, even though the removed e-node was its source.
+    let comm = lookup_rec_expr(&parse_arith("(add 2 (var $0))"), &eg).unwrap();
+    assert!(eg.eq(&a, &comm));
+}
+
+#[test]
+fn prune_unreachable() {
+    let mut eg = EGraph::<Arith>::new();
+    let root = eg.add_expr(parse_arith("(add (var $0) (var $1))"));
+    let garbage = parse_arith("(mul (var $2) 3)");
+    let g = eg.add_expr(garbage.clone());
+
+    // `(var $2)` is shared with the root, so only `3` and the `mul` get removed.
+    assert_eq!(eg.prune(&[root.id]), 2);
+    assert!(eg.is_pruned(g.id));
+    assert!(!eg.ids().contains(&g.id));
+    assert!(lookup_rec_expr(&garbage, &eg).is_none());
+    assert_eq!(eg.prune(&[root.id]), 0);
+
+    // The e-graph can still be used normally.
+    let g2 = eg.add_expr(garbage.clone());
+    assert_ne!(g2.id, g.id);
+    rewrite_arith(&mut eg);
+    let comm = lookup_rec_expr(&parse_arith("(add (var $1) (var $0))"), &eg).unwrap();
+    assert!(eg.eq(&root, &comm));
+}
+
+#[test]
+#[cfg(not(feature = "explanations"))]
+fn compact() {
+    let (mut eg, root) = saturate_arith("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))", 1);
+    let start = parse_arith("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))");
+    let goal = parse_arith("(mul (add (var $2) (var $1)) (add (var $1) (var $0)))");
+
+    // `root` might be dead by now, and is hence normalized first.
+    let root = eg.find_applied_id(&root);
+    let before = eg.progress();
+    let map = eg.compact();
+    let after = eg.progress();
+
+    assert!(after.number_of_classes < before.number_of_classes);
+    assert_eq!(after.number_of_live_classes, before.number_of_live_classes);
+    assert_eq!(eg.ids(), (0..after.number_of_live_classes).map(Id).collect::<Vec<_>>());
+
+    let root = AppliedId::new(map[&root.id], root.m);
+    assert_eq!(lookup_rec_expr(&start, &eg), Some(root.clone()));
+
+    for _ in 0..2 {
+        rewrite_arith(&mut eg);
+        eg.compact();
+    }
+    let i1 = lookup_rec_expr(&start, &eg).unwrap();
+    let i2 = lookup_rec_expr(&goal, &eg).unwrap();
+    assert!(eg.eq(&i1, &i2));
+}
@@ -1,7 +1,11 @@
 use crate::*;
 
 pub fn rewrite_arith(eg: &mut EGraph<Arith>) {
-    apply_rewrites(eg, &[
+    apply_rewrites(eg, &arith_rules());
+}
+
+pub fn arith_rules() -> Vec<Rewrite<Arith>> {
+    vec![
         beta(),
         eta(),
 
@@ -20,7 +24,7 @@ pub fn rewrite_arith(eg: &mut EGraph<Arith>) {
 
         distr1(),
         distr2(),
-    ]);
+    ]
 }
 
 fn beta() -> Rewrite<Arith> {
@@ -31,12 +35,10 @@ fn beta() -> Rewrite<Arith> {
 }
 
 fn eta() -> Rewrite<Arith> {
-    let pat = "(lam $1 (app ?b (var $1)))";
+    let pat = "(lam $1 (app ?b[-$1] (var $1)))";
     let outpat = "?b";
 
-    Rewrite::new_if("eta", pat, outpat, |subst, _| {
-        !subst["b"].slots().contains(&Slot::numeric(1))
-    })
+    Rewrite::new("eta", pat, outpat)
 }
 
 fn eta_expansion() -> Rewrite<Arith> {
@@ -46,11 +48,9 @@ fn eta_expansion() -> Rewrite<Arith> {
 }
 
 fn my_let_unused() -> Rewrite<Arith> {
-    let pat = "(let $1 ?t ?b)";
+    let pat = "(let $1 ?t ?b[-$1])";
     let outpat = "?b";
-    Rewrite::new_if("my-let-unused", pat, outpat, |subst, _| {
-        !subst["b"].slots().contains(&Slot::numeric(1))
-    })
+    Rewrite::new("my-let-unused", pat, outpat)
 }
 
 fn let_var_same() -> Rewrite<Arith> {
@@ -0,0 +1,68 @@
+use crate::*;
+
+fn arith_conditions() -> ConditionRegistry<Arith> {
+    let uses_1 = |subst: &Subst, v: &str| subst[v].slots().contains(&Slot::numeric(1));
+    let mut conds = ConditionRegistry::new();
+    conds.add("a-or-b-uses-1", move |subst, _| uses_1(subst, "a") || uses_1(subst, "b"));
+    conds.add("b-uses-1", move |subst, _| uses_1(subst, "b"));
+    conds
+}
+
+#[test]
+fn rule_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/arith/arith.rules");
+    let rules = load_rules(path, &arith_conditions()).unwrap();
+    assert_eq!(rules.len(), arith_rules().len());
+    assert!(rules.iter().any(|rw| rw.rule().unwrap().name == "distr-rev"));
+
+    // some equations from the tests in `tst`.
+    for (a, b) in [
+        ("(add (var $0) (add (var $1) (var $2)))", "(add (var $2) (add (var $1) (var $0)))"),
+        ("(mul (var $2) (add (var $0) (var $1)))", "(mul (var $2) (add (var $1) (var $0)))"),
+        ("(app (lam $0 (add (var $0) (var $1))) (var $2))", "(let $3 (var $2) (add (var $1) (var $3)))"),
+    ] {
+        let mut eg: EGraph<Arith> = EGraph::new();
+        let i = eg.add_expr(parse_arith(a));
+        let j = eg.add_expr(parse_arith(b));
+        for _ in 0..4 {
+            apply_rewrites(&mut eg, &rules);
+        }
+        assert!(eg.eq(&i, &j));
+    }
+}
+
+#[test]
+fn rule_file_errors() {
+    let err = |s: &str| parse_rules::<Arith, ()>(s, "test.rules", &arith_conditions()).err().unwrap();
+
+    let e = err("# comment\n\nadd-comm (add ?a ?b) => (add ?b ?a)");
+    assert!(matches!(e.kind, RuleFileErrorKind::MissingName));
+    assert_eq!(e.line, 3);
+
+    let e = err("x: (add ?a ?b) => (add ?b ?a) => ?a");
+    assert_eq!(e.to_string(), "test.rules:1: expected exactly one `=>` or `<=>`");
+
+    let e = err("\nx: (add ?a ?b) => (add ?b ?a");
+    assert_eq!(e.to_string(), "test.rules:2:29: expected `)`, found end of input");
+
+    let e = err("x: => ?a");
+    assert_eq!(e.to_string(), "test.rules:1:4: expected a term, found end of input");
+
+    let e = err("x: (let $1 ?e ?b) => ?b if b-uses-1, b-uses-2");
+    assert!(matches!(&e.kind, RuleFileErrorKind::UnknownCondition(c) if c == "b-uses-2"));
+
+    let e = err("x: ?a => ?a if");
+    assert!(matches!(e.kind, RuleFileErrorKind::MissingCondition));
+
+    let e = err("x: (add ?a ?b) <=> (add ?b ?a)\nx-rev: ?a => ?a");
+    assert_eq!(e.to_string(), "test.rules:2: duplicate rule name `x-rev`");
+
+    let e = load_rules::<Arith, ()>("does-not-exist.rules", &arith_conditions()).err().unwrap();
+    assert!(matches!(e.kind, RuleFileErrorKind::Io(_)));
+}
+
+#[test]
+fn bidirectional_rule_file() {
+    let e = parse_rules::<Arith, ()>("beta: (app (lam $1 ?b) ?t) <=> ?b[(var $1) := ?t]", "test.rules", &arith_conditions()).err().unwrap();
+    assert_eq!(e.to_string(), "test.rules:1: rule 'beta-rev': substitutions `b[x := t]` are only allowed on the rhs");
+}
@@ -0,0 +1,88 @@
+use crate::*;
+
+#[test]
+fn runner_reports_rebuild_time() {
+    let (mut eg, _) = saturate_arith("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))", 0);
+    let report = run_eqsat(&mut eg, arith_rules(), 3, 60, |_| Ok(()));
+    assert!(eg.is_clean());
+    assert!(report.rebuild_time > 0.0);
+    assert!(report.rebuild_time <= report.total_time);
+}
+
+#[test]
+fn runner_goals() {
+    let start = parse_arith("(mul (add (var $0) (var $1)) (var $2))");
+    let comm = parse_arith("(mul (var $2) (add (var $1) (var $0)))");
+    let unreachable = parse_arith("(mul (var $0) (var $1))");
+
+    let mut eg = EGraph::new();
+    let i = eg.add_expr(start.clone());
+    let three = eg.add_expr(parse_arith("3"));
+    let goals = Goals::any(vec![Goal::Equal(i.clone(), three.clone()), Goal::Term(i.clone(), comm.clone())]);
+    let report = run_eqsat_goals(&mut eg, &arith_rules(), &goals, Limits::new(10, 60), |_| Ok(()));
+    assert!(matches!(report.stop_reason, StopReason::GoalsReached));
+    assert_eq!(report.reached_goals, vec![1]);
+    assert!(report.iterations > 0);
+
+    // Goals that are already reached stop immediately.
+    let report = run_eqsat_goals(&mut eg, &arith_rules(), &goals, Limits::new(10, 60), |_| Ok(()));
+    assert!(matches!(report.stop_reason, StopReason::GoalsReached));
+    assert_eq!(report.iterations, 0);
+
+    let goals = Goals::all(vec![Goal::Term(i.clone(), comm), Goal::Term(i, unreachable)]);
+    let report = run_eqsat_goals(&mut eg, &arith_rules(), &goals, Limits::new(2, 60), |_| Ok(()));
+    assert!(!matches!(report.stop_reason, StopReason::GoalsReached));
+    assert_eq!(report.reached_goals, vec![0]);
+}
+
+#[test]
+fn runner_counts_iterations() {
+    let start = "(mul (add (var $0) (var $1)) (var $2))";
+    let goal = parse_arith("(mul (var $2) (add (var $1) (var $0)))");
+    let run = |iter_limit| {
+        let (mut eg, i) = saturate_arith(start, 0);
+        let goals = Goals::any(vec![Goal::Term(i, goal.clone())]);
+        run_eqsat_goals(&mut eg, &arith_rules(), &goals, Limits::new(iter_limit, 60), |_| Ok(()))
+    };
+
+    // The round that reaches the goal is counted.
+    let report = run(10);
+    assert!(matches!(report.stop_reason, StopReason::GoalsReached));
+    let n = report.iterations;
+    assert!(n > 0);
+
+    // The iteration limit bounds the number of rounds.
+    let report = run(n - 1);
+    assert!(matches!(report.stop_reason, StopReason::IterationLimit));
+    assert_eq!(report.iterations, n - 1);
+
+    // `run_eqsat` applies the rewrites once more than its limit.
+    let (mut eg, _) = saturate_arith(start, 0);
+    let report = run_eqsat(&mut eg, arith_rules(), 1, 60, |_| Ok(()));
+    assert_eq!(report.iterations, 2);
+    assert_eq!(eg.total_number_of_nodes(), saturate_arith(start, 2).0.total_number_of_nodes());
+}
+
+#[test]
+fn memory_usage() {
+    let mut eg = EGraph::<Arith>::new();
+    let empty = eg.memory_usage();
+    eg.add_expr(parse_arith("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))"));
+    rewrite_arith(&mut eg);
+
+    let usage = eg.memory_usage();
+    assert!(usage.total() > empty.total());
+    assert!(usage.hashcons > 0 && usage.classes > 0 && usage.unionfind > 0);
+    #[cfg(not(feature = "explanations"))]
+    assert_eq!(usage.proof_registry, 0);
+    #[cfg(feature = "explanations")]
+    assert!(usage.proof_registry > 0);
+}
+
+#[test]
+fn runner_memory_limit() {
+    let (mut eg, _) = saturate_arith("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))", 0);
+    let report = run_eqsat_goals(&mut eg, &arith_rules(), &Goals::none(), Limits::new(100, 60).with_memory_limit(1), |_| Ok(()));
+    assert!(matches!(report.stop_reason, StopReason::MemoryLimit));
+    assert_eq!(report.iterations, 0);
+}
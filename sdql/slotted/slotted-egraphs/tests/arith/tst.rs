@@ -12,7 +12,13 @@ fn assert_reaches(start: &str, goal: &str, steps: usize) {
             let i1 = lookup_rec_expr(&start, &eg).unwrap();
             if eg.eq(&i1, &i2) {
                 #[cfg(feature = "explanations")]
-                println!("{}", eg.explain_equivalence(start, goal).to_string(&eg));
+                {
+                    let expl = eg.explain_equivalence(start.clone(), goal.clone());
+                    println!("{}", expl.to_string(&eg));
+
+                    let rules: Vec<Rule<Arith>> = arith_rules().iter().filter_map(|rw| rw.rule().cloned()).collect();
+                    expl.to_certificate(&eg, &rules).unwrap().check_equation(&start, &goal, &rules).unwrap();
+                }
                 return;
             }
         }
@@ -122,3 +128,259 @@ fn t6() { // z*(x+y) = z*(y+x)
         assert!(false);
     }
 }
+
+#[test]
+fn fork_and_clone_are_independent() {
+    let (mut eg, i1) = saturate_arith("(mul (var $0) (add (var $1) (var $2)))", 1);
+    let start = parse_arith("(mul (var $0) (add (var $1) (var $2)))");
+    let goal = parse_arith("(add (mul (var $2) (var $0)) (mul (var $0) (var $1)))");
+
+    let mut forked = eg.fork();
+    let mut cloned = eg.clone();
+
+    // allocates new e-classes in `eg`, which `forked` and `cloned` will use for different terms.
+    eg.add_expr(parse_arith("(add (var $3) (var $4))"));
+
+    for g in [&mut forked, &mut cloned] {
+        for _ in 0..3 {
+            rewrite_arith(g);
+        }
+        let i2 = lookup_rec_expr(&goal, g).unwrap();
+        assert!(g.eq(&i1, &i2));
+        #[cfg(feature = "explanations")]
+        println!("{}", g.explain_equivalence(start.clone(), goal.clone()).to_string(g));
+    }
+
+    assert!(lookup_rec_expr(&goal, &eg).is_none());
+}
+
+// With the `parallel` feature, the searchers run concurrently.
+// The resulting e-graph should nevertheless not depend on the scheduling.
+#[cfg(feature = "parallel")]
+#[test]
+fn rewrites_apply_deterministically() {
+    let run = || saturate_arith("(mul (add (var $0) (var $1)) (add (var $1) (var $2)))", 3).0;
+
+    let eg1 = run();
+    let eg2 = run();
+    assert_eq!(eg1.ids().len(), eg2.ids().len());
+    assert_eq!(eg1.total_number_of_nodes(), eg2.total_number_of_nodes());
+    assert_same_enodes(&eg1, &eg2);
+    assert_same_enodes(&eg2, &eg1);
+}
+
+// Every e-class of `a` has a distinct counterpart in `b`, which contains all of its e-nodes.
+// Both the slot names and the ids depend on the fresh slots handed out before, so e-nodes are compared as terms.
+#[cfg(feature = "parallel")]
+fn assert_same_enodes(a: &EGraph<Arith>, b: &EGraph<Arith>) {
+    let mut counterparts = HashSet::default();
+    for i in a.ids() {
+        let mut found = None;
+        for n in a.enodes(i) {
+            let re = RecExpr {
+                children: n.applied_id_occurences().iter().map(|x| a.get_syn_expr(x)).collect(),
+                node: n,
+            };
+            let j = lookup_rec_expr(&re, b).unwrap_or_else(|| panic!("{re} is missing")).id;
+            assert!(*found.get_or_insert(j) == j, "{re} is in another e-class");
+        }
+        assert!(counterparts.insert(found.unwrap()), "two e-classes have the same counterpart");
+    }
+}
+
+#[test]
+fn unions_are_rebuilt_lazily() {
+    let p = parse_arith;
+    let mut eg = EGraph::<Arith>::new();
+    let a = eg.add_expr(p("a"));
+    let b = eg.add_expr(p("b"));
+    let a2 = eg.add_expr(p("(mul a 2)"));
+    let b2 = eg.add_expr(p("(mul b 2)"));
+
+    eg.union(&a, &b);
+    assert!(eg.eq(&a, &b));
+
+    // the congruence a*2 = b*2 is only found by the rebuild.
+    assert!(!eg.is_clean());
+    assert!(!eg.eq(&a2, &b2));
+
+    eg.rebuild();
+    assert!(eg.is_clean());
+    assert!(eg.eq(&a2, &b2));
+}
+
+#[test]
+fn slots_are_shared_between_threads() {
+    let s = "(lam $thread_x (add (var $thread_x) (var $thread_y)))";
+
+    let (re, fresh) = std::thread::spawn(move || {
+        let re = RecExpr::<Arith>::parse(s).unwrap();
+        let fresh: Vec<Slot> = (0..100).map(|_| Slot::fresh()).collect();
+        (re, fresh)
+    }).join().unwrap();
+
+    // named slots mean the same on every thread.
+    assert_eq!(re.to_string(), s);
+    assert_eq!(RecExpr::<Arith>::parse(s).unwrap(), re);
+
+    // fresh slots never collide.
+    let fresh2: HashSet<Slot> = (0..100).map(|_| Slot::fresh()).collect();
+    assert!(fresh.iter().all(|x| !fresh2.contains(x)));
+}
+
+// E-graphs are only `Send` with the `parallel` feature, as the [SubstMethod] need not be `Send` otherwise.
+#[cfg(feature = "parallel")]
+#[test]
+fn egraphs_move_between_threads() {
+    let s = "(lam $thread_x (add (var $thread_x) (var $thread_y)))";
+
+    let (re, eg) = std::thread::spawn(move || {
+        let re = RecExpr::<Arith>::parse(s).unwrap();
+        let mut eg: EGraph<Arith> = EGraph::new();
+        eg.add_expr(re.clone());
+        (re, eg)
+    }).join().unwrap();
+
+    assert!(lookup_rec_expr(&re, &eg).is_some());
+}
+
+#[test]
+fn parse_errors() {
+    let err = |s: &str| RecExpr::<Arith>::parse(s).unwrap_err();
+    let span = |line, col| Span { line, col };
+
+    let e = err("(add (var $0)");
+    assert!(matches!(e, ParseError::UnexpectedEnd { .. }));
+    assert_eq!(e.span(), span(1, 14));
+    assert_eq!(e.to_string(), "1:14: expected `)`, found end of input");
+
+    assert!(matches!(err("(add a b) c"), ParseError::UnexpectedToken { span: Span { line: 1, col: 11 }, .. }));
+    assert!(matches!(err("(var $)"), ParseError::MissingName(Span { line: 1, col: 6 })));
+    assert!(matches!(err("(foo a b)"), ParseError::FromOpFailed { span: Span { line: 1, col: 2 }, .. }));
+    assert!(matches!(err(""), ParseError::UnexpectedEnd { span: Span { line: 1, col: 1 }, .. }));
+
+    let e = err("(add a\n  ])");
+    assert_eq!(e.span(), span(2, 3));
+    assert_eq!(e.to_string(), "2:3: expected a term, found `]`");
+
+    let e = Pattern::<Arith>::parse("?a[?b := ?c").unwrap_err();
+    assert_eq!(e.to_string(), "1:12: expected `]`, found end of input");
+
+    // slot indices that don't fit are just names.
+    assert!(RecExpr::<Arith>::parse("(var $18446744073709551615)").is_ok());
+
+    // ParseError is a proper error type.
+    let _: Box<dyn std::error::Error> = Box::new(e);
+}
+
+#[test]
+fn parse_never_panics() {
+    // every string of up to 4 characters from this alphabet.
+    let alphabet = ['(', ')', '[', ']', '$', '?', ':', '=', '-', 'a', ' '];
+    let mut strings = vec![String::new()];
+    for _ in 0..4 {
+        let last = strings.clone();
+        for s in last {
+            for c in alphabet {
+                let s = format!("{s}{c}");
+                let _ = Pattern::<Arith>::parse(&s);
+                let _ = RecExpr::<Arith>::parse(&s);
+                strings.push(s);
+            }
+        }
+    }
+}
+
+#[test]
+fn slot_constraints_parse() {
+    for s in ["(lam $1 (app ?f[-$1] (var $1)))", "(lam $x (lam $y ?b[$x $y]))", "(add ?a[] ?b[-$0 -$1])", "?b[$0][(var $0) := ?t]"] {
+        assert_eq!(Pattern::<Arith>::parse(s).unwrap().to_string(), s);
+    }
+
+    let e = Pattern::<Arith>::parse("?f[-$1 $2]").unwrap_err();
+    assert_eq!(e.to_string(), "1:8: expected an excluded slot `-$x` or `]`, found `$2`");
+    let e = Pattern::<Arith>::parse("?f[$1 -$2]").unwrap_err();
+    assert_eq!(e.to_string(), "1:7: expected a slot `$x` or `]`, found `-$2`");
+    assert!(RecExpr::<Arith>::parse("(lam -$1 (var $1))").is_err());
+}
+
+// A constrained pattern-variable finds the same matches as an unconstrained one with the corresponding condition.
+#[test]
+fn slot_constraints_ematch() {
+    let mut eg: EGraph<Arith> = EGraph::new();
+    for s in [
+        "(lam $0 (app (var $1) (var $0)))",
+        "(lam $0 (app (var $0) (var $0)))",
+        "(lam $0 (app (lam $2 (var $2)) (var $0)))",
+        "(lam $0 (var $0))",
+        "(lam $0 (add (var $0) (var $1)))",
+        "(lam $0 (add (var $2) (var $1)))",
+        "(lam $0 3)",
+    ] {
+        eg.add_expr(parse_arith(s));
+    }
+
+    let count = |pat: &str, f: &dyn Fn(&Subst) -> bool| {
+        ematch_all(&eg, &Pattern::parse(pat).unwrap()).iter().filter(|x| f(x)).count()
+    };
+
+    let without = count("(lam $0 (app ?f[-$0] (var $0)))", &|_| true);
+    assert_eq!(without, 2);
+    assert_eq!(without, count("(lam $0 (app ?f (var $0)))", &|subst| !subst["f"].slots().contains(&Slot::numeric(0))));
+
+    let only = count("(lam $0 ?b[$0])", &|_| true);
+    assert_eq!(only, 4);
+    assert_eq!(only, count("(lam $0 ?b)", &|subst| subst["b"].slots().iter().all(|s| *s == Slot::numeric(0))));
+
+    assert_eq!(count("(lam $0 ?b[])", &|_| true), 1);
+}
+
+#[test]
+fn invalid_rewrites() {
+    let err = |a: &str, b: &str| Rewrite::<Arith>::try_new("r", a, b).err().unwrap();
+
+    assert!(Rewrite::<Arith>::try_new("r", "(add ?a ?b)", "(add ?b ?a)").is_ok());
+    assert!(Rewrite::<Arith>::try_new("r", "?b", "(lam $1 (app ?b (var $1)))").is_ok());
+
+    let e = err("(add ?a ?b)", "(mul ?a ?c)");
+    assert!(matches!(&e, RuleError::UnboundVar { var, .. } if var == "c"));
+
+    let e = err("(lam $0 (var $0))", "(var $0)");
+    assert!(matches!(e, RuleError::UnboundSlot { slot, .. } if slot == Slot::numeric(0)));
+
+    let e = err("(add (var $0) ?a)", "(lam $0 (add (var $0) ?a))");
+    assert_eq!(e.to_string(), "rule 'r': a binder on the rhs shadows the free slot `$0` of the lhs");
+
+    assert!(matches!(err("(add ?a", "?a"), RuleError::Parse { .. }));
+    assert!(matches!(err("?b[(var $0) := ?a]", "?b"), RuleError::SubstOnLhs { .. }));
+
+    let e = parse_rules::<Arith, ()>("\n\nr: (add ?a ?b) => ?c", "test.rules", &ConditionRegistry::new()).err().unwrap();
+    assert_eq!(e.to_string(), "test.rules:3: rule 'r': the rhs uses `?c`, which doesn't occur on the lhs");
+}
+
+#[test]
+#[should_panic(expected = "rule 'r': the rhs uses `?c`, which doesn't occur on the lhs")]
+fn invalid_rewrite_panics_at_construction() {
+    Rewrite::<Arith>::new("r", "(add ?a ?b)", "(add ?b ?c)");
+}
+
+#[test]
+fn unchecked_rewrite() {
+    // The rhs introduces the free slot `$1`, which `try_new` rejects.
+    assert!(Rewrite::<Arith>::try_new("r", "(lam $0 ?b)", "(lam $0 (add ?b (var $1)))").is_err());
+    let rw = Rewrite::<Arith>::new_unchecked("r", "(lam $0 ?b)", "(lam $0 (add ?b (var $1)))");
+    assert_eq!(rw.rule().unwrap().name, "r");
+}
+
+#[test]
+fn sum_symmetries() {
+    let [x, y, z] = [0, 1, 2].map(Slot::numeric);
+    let (mut eg, i) = saturate_arith("(add (var $0) (add (var $1) (var $2)))", 3);
+
+    let sym = eg.symmetries(&i);
+    assert_eq!(sym.order, 6);
+    assert_eq!(sym.orbits, vec![vec![x, y, z]]);
+    assert!(sym.contains(&SlotMap::from([(x, y), (y, z), (z, x)])));
+    assert!(sym.is_generated_by(&[SlotMap::from([(x, y), (y, x), (z, z)]), SlotMap::from([(x, y), (y, z), (z, x)])]));
+    assert!(!sym.is_generated_by(&[SlotMap::from([(x, y), (y, x), (z, z)])]));
+}
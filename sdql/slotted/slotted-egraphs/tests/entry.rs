@@ -58,6 +58,13 @@ pub fn equate<L: Language>(s1: &str, s2: &str, eg: &mut EGraph<L>) {
     eg.check();
 }
 
+// Asserts that the symmetry group of the e-class of `s` is generated by `perms`.
+pub fn assert_symmetries<L: Language>(s: &str, perms: &[SlotMap], eg: &mut EGraph<L>) {
+    let i = id(s, eg);
+    let sym = eg.symmetries(&i);
+    assert!(sym.is_generated_by(perms), "expected the symmetries of {s} to be generated by {perms:?}, found {sym:?}");
+}
+
 pub fn explain<L: Language>(s1: &str, s2: &str, eg: &mut EGraph<L>) {
     eg.check();
     let s1 = term(s1, eg);
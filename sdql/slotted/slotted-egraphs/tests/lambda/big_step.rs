@@ -41,6 +41,7 @@ pub fn rewrite_big_step(eg: &mut EGraph<Lambda>) {
         let new_id = subst(b, x, t, eg);
         eg.union_justified(&new_id, &app_id, Some("big-step-beta-reduction".to_string()));
     }
+    eg.rebuild();
 }
 
 pub fn candidates(eg: &EGraph<Lambda>) -> Vec<Candidate> {
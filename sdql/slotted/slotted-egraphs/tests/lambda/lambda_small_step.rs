@@ -45,6 +45,7 @@ pub fn rewrite_small_step(eg: &mut EGraph<Lambda>) {
     for (x, y) in future_unions {
         eg.union_justified(&x, &y, Some("beta-rewrite-small-step".to_string()));
     }
+    eg.rebuild();
 }
 
 // everything here has L0 slot-names.
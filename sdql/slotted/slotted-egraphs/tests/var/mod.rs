@@ -51,3 +51,28 @@ fn xy_eq_yz_causes_redundancy() {
     let id = ids[0];
     assert!(eg.slots(id).is_empty());
 }
+
+#[test]
+fn swap_symmetry() {
+    let x = Slot::numeric(0);
+    let y = Slot::numeric(1);
+    let mut eg = EGraph::<Var>::new();
+    assert_symmetries("(f $0 $1)", &[], &mut eg);
+
+    equate("(f $0 $1)", "(f $1 $0)", &mut eg);
+    assert_symmetries("(f $0 $1)", &[SlotMap::from([(x, y), (y, x)])], &mut eg);
+
+    let i = id("(f $0 $1)", &mut eg);
+    let sym = eg.symmetries(&i);
+    assert_eq!(sym.order, 2);
+    assert_eq!(sym.orbits, vec![vec![x, y]]);
+    assert!(!sym.contains(&SlotMap::from([(x, x)])));
+
+    // The symmetries talk about the slots of the given AppliedId.
+    let a = Slot::numeric(3);
+    let b = Slot::numeric(7);
+    let i = id("(f $7 $3)", &mut eg);
+    let sym = eg.symmetries(&i);
+    assert_eq!(sym.slots, vec![a, b]);
+    assert_eq!(sym.generators, vec![SlotMap::from([(a, b), (b, a)])]);
+}
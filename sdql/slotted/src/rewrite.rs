@@ -2,13 +2,11 @@ use crate::*;
 
 type SdqlRewrite = Rewrite<Sdql, SdqlKind>;
 
+// A `new_bidir` pair is named `x` and `x-rev`, and is annotated with both of its egg counterparts.
 // rw!("mult-assoc1"; "(* (* ?a ?b) ?c)" => "(* ?a (* ?b ?c))"),
-fn mult_assoc1() -> SdqlRewrite {
-    Rewrite::new("mult-assoc1", "(* (* ?a ?b) ?c)", "(* ?a (* ?b ?c))")
-}
 // rw!("mult-assoc2"; "(* ?a (* ?b ?c))" => "(* (* ?a ?b) ?c)"),
-fn mult_assoc2() -> SdqlRewrite {
-    Rewrite::new("mult-assoc2", "(* ?a (* ?b ?c))", "(* (* ?a ?b) ?c)")
+fn mult_assoc() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("mult-assoc", "(* (* ?a ?b) ?c)", "(* ?a (* ?b ?c))")
 }
 // rw!("sub-identity";"(- ?e ?e)"        => "0"),
 fn sub_identity() -> SdqlRewrite {
@@ -27,69 +25,45 @@ fn eq_comm() -> SdqlRewrite {
     Rewrite::new("eq-comm", "(eq ?a ?b)", "(eq ?b ?a)")
 }
 // rw!("mult-app1"; "(* ?a ?b)" => "(binop mult ?a ?b)"),
-fn mult_app1() -> SdqlRewrite {
-    Rewrite::new("mult-app1", "(* ?a ?b)", "(binop mult ?a ?b)")
-}
 // rw!("mult-app2"; "(binop mult ?a ?b)" => "(* ?a ?b)"),
-fn mult_app2() -> SdqlRewrite {
-    Rewrite::new("mult-app2", "(binop mult ?a ?b)", "(* ?a ?b)")
+fn mult_app() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("mult-app", "(* ?a ?b)", "(binop mult ?a ?b)")
 }
 // rw!("add-app1"; "(+ ?a ?b)" => "(binop add ?a ?b)"),
-fn add_app1() -> SdqlRewrite {
-    Rewrite::new("add-app1", "(+ ?a ?b)", "(binop add ?a ?b)")
-}
 // rw!("add-app2"; "(binop add ?a ?b)" => "(+ ?a ?b)"),
-fn add_app2() -> SdqlRewrite {
-    Rewrite::new("add-app2", "(binop add ?a ?b)", "(+ ?a ?b)")
+fn add_app() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("add-app", "(+ ?a ?b)", "(binop add ?a ?b)")
 }
 // rw!("sub-app1"; "(- ?a ?b)" => "(binop sub ?a ?b)"),
-fn sub_app1() -> SdqlRewrite {
-    Rewrite::new("sub-app1", "(- ?a ?b)", "(binop sub ?a ?b)")
-}
 // rw!("sub-app2"; "(binop sub ?a ?b)" => "(- ?a ?b)"),
-fn sub_app2() -> SdqlRewrite {
-    Rewrite::new("sub-app2", "(binop sub ?a ?b)", "(- ?a ?b)")
+fn sub_app() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("sub-app", "(- ?a ?b)", "(binop sub ?a ?b)")
 }
 // rw!("get-app1"; "(get ?a ?b)" => "(binop getf ?a ?b)"),
-fn get_app1() -> SdqlRewrite {
-    Rewrite::new("get-app1", "(get ?a ?b)", "(binop getf ?a ?b)")
-}
 // rw!("get-app2"; "(binop getf ?a ?b)" => "(get ?a ?b)"),
-fn get_app2() -> SdqlRewrite {
-    Rewrite::new("get-app2", "(binop getf ?a ?b)", "(get ?a ?b)")
+fn get_app() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("get-app", "(get ?a ?b)", "(binop getf ?a ?b)")
 }
 // rw!("sing-app1"; "(sing ?a ?b)" => "(binop singf ?a ?b)"),
-fn sing_app1() -> SdqlRewrite {
-    Rewrite::new("sing-app1", "(sing ?a ?b)", "(binop singf ?a ?b)")
-}
 // rw!("sing-app2"; "(binop singf ?a ?b)" => "(sing ?a ?b)"),
-fn sing_app2() -> SdqlRewrite {
-    Rewrite::new("sing-app2", "(binop singf ?a ?b)", "(sing ?a ?b)")
+fn sing_app() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("sing-app", "(sing ?a ?b)", "(binop singf ?a ?b)")
 }
 // rw!("unique-app1"; "(unique ?a)" => "(apply uniquef ?a)"),
-fn unique_app1() -> SdqlRewrite {
-    Rewrite::new("unique-app1", "(unique ?a)", "(apply uniquef ?a)")
-}
 // rw!("unique-app2"; "(apply uniquef ?a)" => "(unique ?a)"),
-fn unique_app2() -> SdqlRewrite {
-    Rewrite::new("unique-app2", "(apply uniquef ?a)", "(unique ?a)")
+fn unique_app() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("unique-app", "(unique ?a)", "(apply uniquef ?a)")
 }
 
 // rw!("let-binop3"; "(let ?e1 (binop ?f ?e2 ?e3))" => "(binop ?f (let ?e1 ?e2) (let ?e1 ?e3))"),
-fn let_binop3() -> SdqlRewrite {    
-    Rewrite::new("let-binop3", "(let $x ?e1 (binop ?f ?e2 ?e3))", "(binop ?f (let $x ?e1 ?e2) (let $x ?e1 ?e3))")
-}
 // rw!("let-binop4"; "(binop ?f (let ?e1 ?e2) (let ?e1 ?e3))" => "(let ?e1 (binop ?f ?e2 ?e3))"),
-fn let_binop4() -> SdqlRewrite {    
-    Rewrite::new("let-binop4", "(binop ?f (let $x ?e1 ?e2) (let $x ?e1 ?e3))", "(let $x ?e1 (binop ?f ?e2 ?e3))")
+fn let_binop() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("let-binop", "(let $x ?e1 (binop ?f ?e2 ?e3))", "(binop ?f (let $x ?e1 ?e2) (let $x ?e1 ?e3))")
 }
 // rw!("let-apply1"; "(let ?e1 (apply ?e2 ?e3))" => "(apply ?e2 (let ?e1 ?e3))"),
-fn let_apply1() -> SdqlRewrite {    
-    Rewrite::new("let-apply1", "(let $x ?e1 (apply ?e2 ?e3))", "(apply ?e2 (let $x ?e1 ?e3))")
-}
 // rw!("let-apply2"; "(apply ?e2 (let ?e1 ?e3))" => "(let ?e1 (apply ?e2 ?e3))"),
-fn let_apply2() -> SdqlRewrite {    
-    Rewrite::new("let-apply2", "(apply ?e2 (let $x ?e1 ?e3))", "(let $x ?e1 (apply ?e2 ?e3))")
+fn let_apply() -> Vec<SdqlRewrite> {
+    Rewrite::new_bidir("let-apply", "(let $x ?e1 (apply ?e2 ?e3))", "(apply ?e2 (let $x ?e1 ?e3))")
 }
 
 // rw!("if-mult2"; "(* ?e1 (ifthen ?e2 ?e3))" => "(ifthen ?e2 (* ?e1 ?e3))"),
@@ -296,46 +270,48 @@ fn unique_rm() -> SdqlRewrite {
 
 pub fn sdql_rules_old() -> Vec<SdqlRewrite> {
 
-    vec![
-      mult_assoc1(), mult_assoc2(), sub_identity(), add_zero(), sub_zero(),
-      eq_comm(),
-      mult_app1(), mult_app2(), add_app1(), add_app2(), sub_app1(), sub_app2(), 
-      get_app1(), get_app2(), sing_app1(), sing_app2(), unique_app1(), unique_app2(),
-      let_binop3(), let_binop4(), let_apply1(), let_apply2(),
-      if_mult2(), if_to_mult(), mult_to_if(),
-      beta(), 
-      sum_fact_1(), sum_fact_2(), sum_fact_3(),
-      sing_mult_1(), sing_mult_2(), sing_mult_3(), sing_mult_4(),
-      sum_fact_inv_1(),
-      sum_sum_vert_fuse_1(),
-      sum_sum_vert_fuse_2(),
-      get_sum_vert_fuse_1(),
-      sum_range_1(), sum_range_2(),
-      sum_merge(),
-      get_to_sum(),
-      sum_sing(), unique_rm()
-      ]
+    [
+      mult_assoc(), vec![sub_identity(), add_zero(), sub_zero(), eq_comm()],
+      mult_app(), add_app(), sub_app(),
+      get_app(), sing_app(), unique_app(),
+      let_binop(), let_apply(),
+      vec![
+        if_mult2(), if_to_mult(), mult_to_if(),
+        beta(), 
+        sum_fact_1(), sum_fact_2(), sum_fact_3(),
+        sing_mult_1(), sing_mult_2(), sing_mult_3(), sing_mult_4(),
+        sum_fact_inv_1(),
+        sum_sum_vert_fuse_1(),
+        sum_sum_vert_fuse_2(),
+        get_sum_vert_fuse_1(),
+        sum_range_1(), sum_range_2(),
+        sum_merge(),
+        get_to_sum(),
+        sum_sing(), unique_rm()
+      ],
+    ].into_iter().flatten().collect()
 }
 
 pub fn sdql_rules() -> Vec<SdqlRewrite> {
 
-    vec![
-      mult_assoc1(), mult_assoc2(), sub_identity(), add_zero(), sub_zero(),
-      eq_comm(),
-      mult_app1(), mult_app2(), add_app1(), add_app2(), sub_app1(), sub_app2(), 
-      get_app1(), get_app2(), sing_app1(), sing_app2(), unique_app1(), unique_app2(),
-      let_binop3(), let_binop4(), let_apply1(), let_apply2(),
-      if_mult2(), if_to_mult(), mult_to_if(),
-      beta(), 
-      sum_fact_1(), sum_fact_2(), sum_fact_3(),
-      sing_mult_1(), sing_mult_2(), sing_mult_3(), sing_mult_4(),
-      sum_fact_inv_1(), sum_fact_inv_3(),
-      sum_sum_vert_fuse_1(),
-      sum_sum_vert_fuse_2(),
-      sum_range_1(), 
-      sum_merge(),
-      get_to_sum(), sum_to_get(), get_range(),
-      sum_sing(), unique_rm()
-      ]
+    [
+      mult_assoc(), vec![sub_identity(), add_zero(), sub_zero(), eq_comm()],
+      mult_app(), add_app(), sub_app(),
+      get_app(), sing_app(), unique_app(),
+      let_binop(), let_apply(),
+      vec![
+        if_mult2(), if_to_mult(), mult_to_if(),
+        beta(), 
+        sum_fact_1(), sum_fact_2(), sum_fact_3(),
+        sing_mult_1(), sing_mult_2(), sing_mult_3(), sing_mult_4(),
+        sum_fact_inv_1(), sum_fact_inv_3(),
+        sum_sum_vert_fuse_1(),
+        sum_sum_vert_fuse_2(),
+        sum_range_1(), 
+        sum_merge(),
+        get_to_sum(), sum_to_get(), get_range(),
+        sum_sing(), unique_rm()
+      ],
+    ].into_iter().flatten().collect()
 }
 